@@ -0,0 +1,391 @@
+//! Runs programs natively against an in-memory ledger.
+//!
+//! The syscall stubs below stand in for the runtime's cross-program
+//! invocation, clock, rent, return data and event logs, and route system, SPL Token and Token-2022
+//! instructions to minimal native processors. Accounts are laid out as the
+//! runtime serializes them, so programs can resize them.
+//!
+//! Each ledger owns its clock and programs. Tests run on threads of their
+//! own, so the state the stubs read while a ledger processes an instruction
+//! is kept per thread.
+
+use std::{cell::RefCell, collections::HashMap};
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE, SUCCESS},
+        instruction::Instruction,
+        program_option::COption,
+        program_pack::Pack,
+        program_stubs::{set_syscall_stubs, SyscallStubs},
+    },
+    system_program,
+};
+use anchor_spl::{token::spl_token, token_2022::spl_token_2022};
+
+pub const BPF_LOADER_UPGRADEABLE: Pubkey =
+    Pubkey::from_str_const("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// Unix time every ledger starts at.
+pub const GENESIS_TIME: i64 = 1_700_000_000;
+
+/// A program's entrypoint, such as the `entry` function Anchor generates.
+pub type Entrypoint = for<'a> fn(&Pubkey, &'a [AccountInfo<'a>], &[u8]) -> ProgramResult;
+
+thread_local! {
+    // Programs currently executing, innermost last
+    static CALL_STACK: RefCell<Vec<Pubkey>> = const { RefCell::new(Vec::new()) };
+    // Programs and clock of the ledger processing an instruction on this thread
+    static PROGRAMS: RefCell<HashMap<Pubkey, Entrypoint>> = RefCell::new(HashMap::new());
+    static CLOCK: RefCell<Clock> = RefCell::new(Clock::default());
+    // Return data of the instruction last processed on this thread
+    static RETURN_DATA: RefCell<Option<(Pubkey, Vec<u8>)>> = const { RefCell::new(None) };
+    // Data logged by the instruction last processed on this thread, such as Anchor events
+    static LOGGED_DATA: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+}
+
+struct Runtime;
+
+impl SyscallStubs for Runtime {
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let caller = CALL_STACK.with(|stack| *stack.borrow().last().unwrap());
+        let pda_signers = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut accounts = Vec::with_capacity(instruction.accounts.len());
+        for meta in &instruction.accounts {
+            let mut info = account_infos
+                .iter()
+                .find(|info| *info.key == meta.pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?
+                .clone();
+            if meta.is_signer && !info.is_signer && !pda_signers.contains(&meta.pubkey) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if meta.is_writable && !info.is_writable {
+                return Err(ProgramError::InvalidArgument);
+            }
+            info.is_signer = meta.is_signer;
+            info.is_writable = meta.is_writable;
+            accounts.push(info);
+        }
+
+        execute(
+            &instruction.program_id,
+            Box::leak(accounts.into_boxed_slice()),
+            &instruction.data,
+        )
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = CLOCK.with(|clock| clock.borrow().clone());
+        unsafe { *(var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        RETURN_DATA.with(|return_data| return_data.borrow().clone())
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        let program = CALL_STACK.with(|stack| *stack.borrow().last().unwrap());
+        let value = (!data.is_empty()).then(|| (program, data.to_vec()));
+        RETURN_DATA.with(|return_data| *return_data.borrow_mut() = value);
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        LOGGED_DATA.with(|logged| logged.borrow_mut().push(fields.concat()));
+    }
+}
+
+fn execute<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], data: &[u8]) -> ProgramResult {
+    let entrypoint = PROGRAMS.with(|programs| programs.borrow().get(program_id).copied());
+    CALL_STACK.with(|stack| stack.borrow_mut().push(*program_id));
+    let result = if let Some(entrypoint) = entrypoint {
+        entrypoint(program_id, accounts, data)
+    } else if *program_id == spl_token::ID {
+        spl_token::processor::Processor::process(program_id, accounts, data)
+    } else if *program_id == spl_token_2022::ID {
+        spl_token_2022::processor::Processor::process(program_id, accounts, data)
+    } else if *program_id == system_program::ID {
+        system_instruction(accounts, data)
+    } else {
+        Err(ProgramError::IncorrectProgramId)
+    };
+    CALL_STACK.with(|stack| stack.borrow_mut().pop());
+    result
+}
+
+/// The system instructions Anchor needs: creating a fresh account and
+/// transferring lamports.
+fn system_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (tag, args) = data.split_at(4);
+    let [from, to, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let lamports = u64::from_le_bytes(args[..8].try_into().unwrap());
+    match tag {
+        [0, 0, 0, 0] => {
+            let space = u64::from_le_bytes(args[8..16].try_into().unwrap());
+            let owner = Pubkey::try_from(&args[16..48]).unwrap();
+            if !from.is_signer || !to.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if to.lamports() != 0 || *to.owner != system_program::ID {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            *to.try_borrow_mut_data()? = account_data(vec![0; space as usize]);
+            to.assign(&owner);
+        }
+        [2, 0, 0, 0] => {
+            if !from.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+    **from.try_borrow_mut_lamports()? -= lamports;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+/// Data with its length in the 8 bytes before it and room to grow behind it,
+/// where `AccountInfo::resize` expects them.
+fn account_data(data: Vec<u8>) -> &'static mut [u8] {
+    let len = data.len();
+    let words = (8 + len + MAX_PERMITTED_DATA_INCREASE).div_ceil(8);
+    let buffer = Box::leak(vec![0u64; words].into_boxed_slice());
+    buffer[0] = len as u64;
+    let bytes =
+        unsafe { std::slice::from_raw_parts_mut(buffer.as_mut_ptr().add(1).cast::<u8>(), len) };
+    bytes.copy_from_slice(&data);
+    bytes
+}
+
+/// Key with the account's original data length in the 4 bytes before it,
+/// where `AccountInfo::resize` reads it.
+fn account_key(key: Pubkey, data_len: usize) -> &'static Pubkey {
+    let buffer = Box::leak(Box::new([0u8; 36]));
+    buffer[..4].copy_from_slice(&(data_len as u32).to_le_bytes());
+    buffer[4..].copy_from_slice(key.as_ref());
+    unsafe { &*buffer.as_ptr().add(4).cast::<Pubkey>() }
+}
+
+/// Accounts of the simulated cluster, leaked so programs can borrow them freely.
+pub struct Ledger {
+    pub accounts: HashMap<Pubkey, AccountInfo<'static>>,
+    programs: HashMap<Pubkey, Entrypoint>,
+    clock: Clock,
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        set_syscall_stubs(Box::new(Runtime));
+        let mut ledger = Self {
+            accounts: HashMap::new(),
+            programs: HashMap::new(),
+            clock: Clock {
+                slot: 1,
+                unix_timestamp: GENESIS_TIME,
+                ..Clock::default()
+            },
+        };
+        for program in [spl_token::ID, spl_token_2022::ID, system_program::ID] {
+            ledger.set(program, system_program::ID, 1, vec![], true);
+        }
+        ledger
+    }
+
+    /// Deploys `entrypoint` as the program at `id`.
+    pub fn add_program(&mut self, id: Pubkey, entrypoint: Entrypoint) {
+        self.set(id, system_program::ID, 1, vec![], true);
+        self.programs.insert(id, entrypoint);
+    }
+
+    /// Deploys `entrypoint` as an upgradeable program whose upgrade authority
+    /// is `authority`. Returns its program data address.
+    pub fn add_upgradeable_program(
+        &mut self,
+        id: Pubkey,
+        entrypoint: Entrypoint,
+        authority: Pubkey,
+    ) -> Pubkey {
+        let (program_data, _) =
+            Pubkey::find_program_address(&[id.as_ref()], &BPF_LOADER_UPGRADEABLE);
+        // bincode UpgradeableLoaderState::Program and ::ProgramData
+        let program = [&[2, 0, 0, 0][..], program_data.as_ref()].concat();
+        let data = [&[3, 0, 0, 0][..], &[0; 8], &[1], authority.as_ref()].concat();
+        self.set(id, BPF_LOADER_UPGRADEABLE, 1, program, true);
+        self.set(program_data, BPF_LOADER_UPGRADEABLE, 1, data, false);
+        self.programs.insert(id, entrypoint);
+        program_data
+    }
+
+    pub fn now(&self) -> i64 {
+        self.clock.unix_timestamp
+    }
+
+    pub fn slot(&self) -> u64 {
+        self.clock.slot
+    }
+
+    pub fn advance_clock(&mut self, seconds: i64) {
+        self.clock.unix_timestamp += seconds;
+    }
+
+    pub fn advance_slots(&mut self, slots: u64) {
+        self.clock.slot += slots;
+    }
+
+    pub fn set(
+        &mut self,
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+        executable: bool,
+    ) {
+        let info = AccountInfo::new(
+            account_key(key, data.len()),
+            false,
+            false,
+            Box::leak(Box::new(lamports)),
+            account_data(data),
+            Box::leak(Box::new(owner)),
+            executable,
+            0,
+        );
+        self.accounts.insert(key, info);
+    }
+
+    /// Stores `account` as an account of `program`, sized for `space` bytes.
+    pub fn set_anchor<T: AccountSerialize>(
+        &mut self,
+        key: Pubkey,
+        program: Pubkey,
+        account: &T,
+        space: usize,
+    ) {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data.resize(space.max(data.len()), 0);
+        self.set(key, program, 1_000_000_000, data, false);
+    }
+
+    pub fn fund(&mut self, key: Pubkey) {
+        self.set(key, system_program::ID, 10_000_000_000, vec![], false);
+    }
+
+    pub fn mint(&mut self, key: Pubkey, authority: Pubkey, supply: u64) {
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        let mint = spl_token::state::Mint {
+            mint_authority: COption::Some(authority),
+            supply,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        spl_token::state::Mint::pack(mint, &mut data).unwrap();
+        self.set(key, spl_token::ID, 1_000_000_000, data, false);
+    }
+
+    pub fn token_account(&mut self, key: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) {
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        let account = spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        spl_token::state::Account::pack(account, &mut data).unwrap();
+        self.set(key, spl_token::ID, 1_000_000_000, data, false);
+    }
+
+    /// Lamports held by `key`, or 0 for an account the ledger never saw.
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.accounts.get(key).map_or(0, |info| info.lamports())
+    }
+
+    /// Balance of an SPL Token or Token-2022 account.
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        let data = self.accounts[key].try_borrow_data().unwrap();
+        spl_token::state::Account::unpack_from_slice(&data[..spl_token::state::Account::LEN])
+            .unwrap()
+            .amount
+    }
+
+    pub fn process(&mut self, ix: Instruction) -> ProgramResult {
+        PROGRAMS.with(|programs| *programs.borrow_mut() = self.programs.clone());
+        CLOCK.with(|clock| *clock.borrow_mut() = self.clock.clone());
+        RETURN_DATA.with(|return_data| *return_data.borrow_mut() = None);
+        LOGGED_DATA.with(|logged| logged.borrow_mut().clear());
+
+        let mut accounts = Vec::with_capacity(ix.accounts.len());
+        for meta in &ix.accounts {
+            if !self.accounts.contains_key(&meta.pubkey) {
+                self.set(meta.pubkey, system_program::ID, 0, vec![], false);
+            }
+            let mut info = self.accounts[&meta.pubkey].clone();
+            info.is_signer = meta.is_signer;
+            info.is_writable = meta.is_writable;
+            accounts.push(info);
+        }
+        // a failed transaction leaves no trace, as on chain
+        let snapshot: Vec<_> = accounts
+            .iter()
+            .map(|info| (info.lamports(), info.data.borrow().to_vec(), *info.owner))
+            .collect();
+        let accounts = Box::leak(accounts.into_boxed_slice());
+        let result = execute(&ix.program_id, accounts, &ix.data);
+        if result.is_err() {
+            for (info, (lamports, data, owner)) in accounts.iter().zip(snapshot) {
+                **info.lamports.borrow_mut() = lamports;
+                *info.data.borrow_mut() = account_data(data);
+                info.assign(&owner);
+            }
+        }
+        result
+    }
+
+    /// Program and data returned by the instruction last processed.
+    pub fn return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        RETURN_DATA.with(|return_data| return_data.borrow().clone())
+    }
+
+    /// Anchor events of type `T` emitted by the instruction last processed.
+    pub fn events<T: anchor_lang::Event>(&self) -> Vec<T> {
+        LOGGED_DATA.with(|logged| {
+            logged
+                .borrow()
+                .iter()
+                .filter_map(|data| data.strip_prefix(T::DISCRIMINATOR))
+                .map(|mut data| T::deserialize(&mut data).unwrap())
+                .collect()
+        })
+    }
+
+    pub fn get<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let data = self.accounts[key].try_borrow_data().unwrap();
+        T::try_deserialize(&mut &data[..]).unwrap()
+    }
+}
+
+impl Default for Ledger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,197 @@
+//! Reward accounting of the staking program, free of any on-chain dependency
+//! so off-chain services compute exactly what the program does.
+//!
+//! Emissions feed a pool-wide accumulator, `reward_per_token_stored`, which
+//! grows by `elapsed * reward_rate / total_staked`. A staker earns
+//! `amount_staked * (reward_per_token_stored - reward_per_token_paid) / PRECISION`.
+//! Both divisions carry their remainder forward instead of dropping it, so
+//! results do not depend on how often the pool is updated or a staker claims,
+//! and stakers are never owed more than was emitted.
+//!
+//! A second accumulator, `points_per_token_stored`, counts the seconds during
+//! which stake was held, giving every staker exact stake-seconds ("points")
+//! whether or not the pool pays a reward token. Points follow their own
+//! weight, `points_staked`, so rewards can be weighted differently.
+#![no_std]
+
+/// Fixed-point scale of `reward_rate` and `reward_per_token_stored`.
+pub const PRECISION: u64 = 1_000_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    Overflow,
+}
+
+pub type Result<T> = core::result::Result<T, MathError>;
+
+/// Pool-wide reward state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolRewards {
+    /// Reward tokens emitted per second, scaled by `PRECISION`.
+    pub reward_rate: u64,
+    /// Rewards per staked token since the pool started, scaled by `PRECISION`.
+    pub reward_per_token_stored: u64,
+    /// Emissions not yet in `reward_per_token_stored`, always below
+    /// `total_staked`, in `reward_rate` units times seconds.
+    pub reward_per_token_remainder: u64,
+    pub last_updated: i64,
+    pub total_staked: u64,
+    /// Reward tokens accrued to stakers but not yet paid out, rounded up.
+    pub rewards_owed: u64,
+    /// Seconds during which the pool held stake, i.e. points per staked token.
+    pub points_per_token_stored: u64,
+    /// Stake-seconds accrued by all stakers together.
+    pub total_stake_points: u128,
+    /// Sum of every staker's `points_staked`.
+    pub total_points_staked: u64,
+}
+
+/// A staker's position against the pool accumulator.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StakerRewards {
+    pub amount_staked: u64,
+    pub reward_per_token_paid: u64,
+    /// Whole reward tokens earned up to `reward_per_token_paid`.
+    pub pending_rewards: u64,
+    /// Fraction of a reward token earned on top of `pending_rewards`, scaled
+    /// by `PRECISION`.
+    pub reward_remainder: u64,
+    pub points_per_token_paid: u64,
+    /// Stake-seconds accrued up to `points_per_token_paid`.
+    pub stake_points: u128,
+    /// Stake earning points, which need not match the reward weight in
+    /// `amount_staked`.
+    pub points_staked: u64,
+}
+
+/// Rate change taking effect at `start_time`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RewardSegment {
+    pub start_time: i64,
+    pub reward_rate: u64,
+}
+
+/// Brings the accumulator up to `current_time`, switching rates at every
+/// segment of `schedule` (ordered by `start_time`) reached on the way.
+/// Returns how many leading segments were applied and can be dropped.
+pub fn update_pool_rewards(
+    pool: &mut PoolRewards,
+    schedule: impl IntoIterator<Item = RewardSegment>,
+    current_time: i64,
+) -> Result<usize> {
+    let mut applied = 0;
+    for segment in schedule {
+        if segment.start_time > current_time {
+            break;
+        }
+        accrue_rewards(pool, segment.start_time)?;
+        pool.reward_rate = segment.reward_rate;
+        applied += 1;
+    }
+    accrue_rewards(pool, current_time)?;
+    Ok(applied)
+}
+
+/// Accrues rewards at the current `reward_rate` up to `until`. Nothing is
+/// emitted while the pool is empty, and no points while no stake earns them.
+fn accrue_rewards(pool: &mut PoolRewards, until: i64) -> Result<()> {
+    if until <= pool.last_updated {
+        return Ok(());
+    }
+    let elapsed = until.abs_diff(pool.last_updated);
+    if pool.total_staked > 0 {
+        add_to_accumulator(pool, elapsed as u128 * pool.reward_rate as u128)?;
+    }
+    if pool.total_points_staked > 0 {
+        pool.points_per_token_stored = pool
+            .points_per_token_stored
+            .checked_add(elapsed)
+            .ok_or(MathError::Overflow)?;
+        pool.total_stake_points = pool
+            .total_stake_points
+            .checked_add(elapsed as u128 * pool.total_points_staked as u128)
+            .ok_or(MathError::Overflow)?;
+    }
+    pool.last_updated = until;
+    Ok(())
+}
+
+/// Shares `amount` reward tokens pro rata among everyone currently staked.
+/// Nothing is distributed when the pool is empty.
+pub fn distribute_rewards(pool: &mut PoolRewards, amount: u64) -> Result<()> {
+    if pool.total_staked == 0 {
+        return Ok(());
+    }
+    add_to_accumulator(pool, amount as u128 * PRECISION as u128)
+}
+
+/// Spreads `emitted` (reward tokens scaled by `PRECISION`) over the staked
+/// tokens, keeping what does not divide evenly for the next update.
+fn add_to_accumulator(pool: &mut PoolRewards, emitted: u128) -> Result<()> {
+    let total_staked = pool.total_staked as u128;
+    let numerator = emitted
+        .checked_add(pool.reward_per_token_remainder as u128)
+        .ok_or(MathError::Overflow)?;
+    let increment = u64::try_from(numerator / total_staked).map_err(|_| MathError::Overflow)?;
+    pool.reward_per_token_stored = pool
+        .reward_per_token_stored
+        .checked_add(increment)
+        .ok_or(MathError::Overflow)?;
+    pool.reward_per_token_remainder = (numerator % total_staked) as u64;
+
+    let accrued = (increment as u128 * total_staked).div_ceil(PRECISION as u128);
+    pool.rewards_owed = u64::try_from(accrued)
+        .ok()
+        .and_then(|accrued| pool.rewards_owed.checked_add(accrued))
+        .ok_or(MathError::Overflow)?;
+    Ok(())
+}
+
+/// Whole reward tokens earned since the staker's last checkpoint, and the
+/// fraction left over.
+fn earned(staker: &StakerRewards, reward_per_token_stored: u64) -> Result<(u64, u64)> {
+    let reward_per_token_diff = reward_per_token_stored
+        .checked_sub(staker.reward_per_token_paid)
+        .ok_or(MathError::Overflow)?;
+    let numerator = staker.amount_staked as u128 * reward_per_token_diff as u128
+        + staker.reward_remainder as u128;
+    let rewards =
+        u64::try_from(numerator / PRECISION as u128).map_err(|_| MathError::Overflow)?;
+    Ok((rewards, (numerator % PRECISION as u128) as u64))
+}
+
+/// Rewards earned since the staker's last checkpoint, on top of `pending_rewards`.
+pub fn calculate_pending_rewards(
+    staker: &StakerRewards,
+    reward_per_token_stored: u64,
+) -> Result<u64> {
+    Ok(earned(staker, reward_per_token_stored)?.0)
+}
+
+/// The staker's stake-seconds up to the pool's last update.
+pub fn calculate_stake_points(staker: &StakerRewards, pool: &PoolRewards) -> Result<u128> {
+    let seconds = pool
+        .points_per_token_stored
+        .checked_sub(staker.points_per_token_paid)
+        .ok_or(MathError::Overflow)?;
+    staker
+        .stake_points
+        .checked_add(staker.points_staked as u128 * seconds as u128)
+        .ok_or(MathError::Overflow)
+}
+
+/// Moves rewards earned since the last checkpoint into `pending_rewards`, and
+/// stake-seconds into `stake_points`.
+/// Call after `update_pool_rewards` and before changing `amount_staked`.
+pub fn accrue_user_rewards(staker: &mut StakerRewards, pool: &PoolRewards) -> Result<()> {
+    let (rewards, remainder) = earned(staker, pool.reward_per_token_stored)?;
+    staker.pending_rewards = staker
+        .pending_rewards
+        .checked_add(rewards)
+        .ok_or(MathError::Overflow)?;
+    staker.reward_remainder = remainder;
+    staker.reward_per_token_paid = pool.reward_per_token_stored;
+    staker.stake_points = calculate_stake_points(staker, pool)?;
+    staker.points_per_token_paid = pool.points_per_token_stored;
+    Ok(())
+}
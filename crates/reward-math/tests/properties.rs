@@ -0,0 +1,211 @@
+use proptest::prelude::*;
+use reward_math::{
+    accrue_user_rewards, distribute_rewards, update_pool_rewards, PoolRewards, RewardSegment,
+    StakerRewards, PRECISION,
+};
+
+const STAKERS: usize = 3;
+
+#[derive(Clone, Debug)]
+enum Action {
+    Wait(i64),
+    Stake(usize, u64),
+    Unstake(usize, u64),
+    SetRate(u64),
+    Distribute(u64),
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        3 => (1i64..100_000).prop_map(Action::Wait),
+        2 => (0..STAKERS, 1u64..1_000_000_000_000).prop_map(|(user, amount)| Action::Stake(user, amount)),
+        1 => (0..STAKERS, 1u64..1_000_000_000_000).prop_map(|(user, amount)| Action::Unstake(user, amount)),
+        1 => (0u64..10_000_000_000).prop_map(Action::SetRate),
+        1 => (1u64..1_000_000).prop_map(Action::Distribute),
+    ]
+}
+
+/// A pool driven the way the staking program drives it, tracking exactly
+/// what was emitted alongside.
+struct Sim {
+    pool: PoolRewards,
+    stakers: [StakerRewards; STAKERS],
+    claimed: [u64; STAKERS],
+    now: i64,
+    /// Reward tokens emitted while anyone was staked, scaled by `PRECISION`.
+    emitted: u128,
+}
+
+impl Sim {
+    fn new(reward_rate: u64) -> Self {
+        Self {
+            pool: PoolRewards {
+                reward_rate,
+                ..PoolRewards::default()
+            },
+            stakers: [StakerRewards::default(); STAKERS],
+            claimed: [0; STAKERS],
+            now: 0,
+            emitted: 0,
+        }
+    }
+
+    fn update(&mut self) {
+        update_pool_rewards(&mut self.pool, core::iter::empty(), self.now).unwrap();
+    }
+
+    fn checkpoint(&mut self, user: usize) {
+        self.update();
+        accrue_user_rewards(&mut self.stakers[user], &self.pool).unwrap();
+    }
+
+    fn claim(&mut self, user: usize) {
+        self.checkpoint(user);
+        let amount = std::mem::take(&mut self.stakers[user].pending_rewards);
+        self.claimed[user] += amount;
+        self.pool.rewards_owed = self.pool.rewards_owed.saturating_sub(amount);
+    }
+
+    fn apply(&mut self, action: &Action) {
+        match *action {
+            Action::Wait(seconds) => {
+                if self.pool.total_staked > 0 {
+                    self.emitted += seconds as u128 * self.pool.reward_rate as u128;
+                }
+                self.now += seconds;
+            }
+            // staker `user` earns rewards on `user + 1` times their stake,
+            // like a boost, while points stay on the stake itself
+            Action::Stake(user, amount) => {
+                self.checkpoint(user);
+                self.stakers[user].points_staked += amount;
+                self.pool.total_points_staked += amount;
+                self.stakers[user].amount_staked += amount * (user as u64 + 1);
+                self.pool.total_staked += amount * (user as u64 + 1);
+            }
+            Action::Unstake(user, amount) => {
+                self.checkpoint(user);
+                let amount = amount.min(self.stakers[user].points_staked);
+                self.stakers[user].points_staked -= amount;
+                self.pool.total_points_staked -= amount;
+                self.stakers[user].amount_staked -= amount * (user as u64 + 1);
+                self.pool.total_staked -= amount * (user as u64 + 1);
+            }
+            Action::SetRate(rate) => {
+                self.update();
+                self.pool.reward_rate = rate;
+            }
+            Action::Distribute(amount) => {
+                self.update();
+                if self.pool.total_staked > 0 {
+                    self.emitted += amount as u128 * PRECISION as u128;
+                }
+                distribute_rewards(&mut self.pool, amount).unwrap();
+            }
+        }
+    }
+
+    /// Checkpoints everyone and returns each staker's lifetime rewards.
+    fn settle(&mut self) -> [u64; STAKERS] {
+        for user in 0..STAKERS {
+            self.checkpoint(user);
+        }
+        core::array::from_fn(|user| self.claimed[user] + self.stakers[user].pending_rewards)
+    }
+}
+
+proptest! {
+    #[test]
+    fn rewards_never_exceed_emissions(
+        rate in 0u64..10_000_000_000,
+        actions in prop::collection::vec(action(), 1..60),
+        claims in prop::collection::vec(any::<Option<usize>>(), 60),
+    ) {
+        let mut sim = Sim::new(rate);
+        for (action, claim) in actions.iter().zip(&claims) {
+            sim.apply(action);
+            if let Some(user) = claim {
+                sim.claim(user % STAKERS);
+            }
+        }
+        let earned = sim.settle();
+
+        let total: u128 = earned.iter().map(|&rewards| rewards as u128).sum();
+        prop_assert!(total * PRECISION as u128 <= sim.emitted);
+        // what is still unclaimed is covered by rewards_owed
+        let unclaimed: u64 = sim.stakers.iter().map(|staker| staker.pending_rewards).sum();
+        prop_assert!(unclaimed <= sim.pool.rewards_owed);
+    }
+
+    #[test]
+    fn claiming_often_pays_the_same_as_claiming_once(
+        rate in 0u64..10_000_000_000,
+        actions in prop::collection::vec(action(), 1..60),
+        claims in prop::collection::vec(any::<Option<usize>>(), 60),
+    ) {
+        let mut once = Sim::new(rate);
+        let mut often = Sim::new(rate);
+        for (action, claim) in actions.iter().zip(&claims) {
+            once.apply(action);
+            often.apply(action);
+            if let Some(user) = claim {
+                often.claim(user % STAKERS);
+            }
+        }
+
+        prop_assert_eq!(once.settle(), often.settle());
+        prop_assert_eq!(once.pool.reward_per_token_stored, often.pool.reward_per_token_stored);
+    }
+
+    #[test]
+    fn stake_points_add_up_to_the_pool_total(
+        rate in 0u64..10_000_000_000,
+        actions in prop::collection::vec(action(), 1..60),
+    ) {
+        let mut sim = Sim::new(rate);
+        let mut points = [0u128; STAKERS];
+        for action in &actions {
+            // every staker earns their current stake for each second waited
+            if let Action::Wait(seconds) = *action {
+                for (user, staker) in sim.stakers.iter().enumerate() {
+                    points[user] += staker.points_staked as u128 * seconds as u128;
+                }
+            }
+            sim.apply(action);
+        }
+        sim.settle();
+
+        let accrued: [u128; STAKERS] = core::array::from_fn(|user| sim.stakers[user].stake_points);
+        prop_assert_eq!(accrued, points);
+        prop_assert_eq!(accrued.iter().sum::<u128>(), sim.pool.total_stake_points);
+    }
+
+    #[test]
+    fn schedule_matches_manual_rate_changes(
+        rate in 0u64..10_000_000_000,
+        stake in 1u64..1_000_000_000_000,
+        segments in prop::collection::vec((1i64..100_000, 0u64..10_000_000_000), 0..8),
+        end in 0i64..1_000_000,
+    ) {
+        let mut schedule = Vec::new();
+        let mut start_time = 0;
+        for (gap, reward_rate) in segments {
+            start_time += gap;
+            schedule.push(RewardSegment { start_time, reward_rate });
+        }
+        let start = PoolRewards { reward_rate: rate, total_staked: stake, ..PoolRewards::default() };
+
+        let mut scheduled = start;
+        let applied = update_pool_rewards(&mut scheduled, schedule.iter().copied(), end).unwrap();
+
+        let mut manual = start;
+        for segment in schedule.iter().take_while(|segment| segment.start_time <= end) {
+            update_pool_rewards(&mut manual, core::iter::empty(), segment.start_time).unwrap();
+            manual.reward_rate = segment.reward_rate;
+        }
+        update_pool_rewards(&mut manual, core::iter::empty(), end).unwrap();
+
+        prop_assert_eq!(applied, schedule.iter().filter(|segment| segment.start_time <= end).count());
+        prop_assert_eq!(scheduled, manual);
+    }
+}
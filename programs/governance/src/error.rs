@@ -28,4 +28,10 @@ pub enum GovernanceError {
     ProposalNotPassed,
     #[msg("Execution delay not met")]
     ExecutionDelayNotMet,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Staking pool required for staked voting power")]
+    MissingStakingPool,
+    #[msg("Staking pool does not stake the voting token")]
+    StakingPoolMintMismatch,
 }
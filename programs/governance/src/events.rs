@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 
-use crate::state::{ProposalStatus, VoteType};
+use crate::state::{ProposalStatus, VoteType, VotingPowerSource};
 
 #[event]
 pub struct RealmInitiated {
@@ -8,6 +8,13 @@ pub struct RealmInitiated {
     pub authority: Pubkey,
 }
 
+#[event]
+pub struct VotingPowerConfigured {
+    pub realm: Pubkey,
+    pub source: VotingPowerSource,
+    pub staking_pool: Pubkey,
+}
+
 #[event]
 pub struct ProposalCreated {
     pub proposal: Pubkey,
@@ -3,7 +3,10 @@ use anchor_spl::token_interface::TokenAccount;
 
 use crate::error::GovernanceError;
 use crate::events::VoteCast;
-use crate::state::{DelegatedVotes, Proposal, ProposalStatus, Realm, VoteRecord, VoteType};
+use crate::state::{
+    DelegatedVotes, Proposal, ProposalStatus, Realm, VoteRecord, VoteType, VotingPowerSource,
+};
+use staking::state::UserStakeAccount;
 
 pub fn process_cast_vote(ctx: Context<CastVote>, vote_type: VoteType) -> Result<()> {
     let proposal = &mut ctx.accounts.proposal;
@@ -23,7 +26,20 @@ pub fn process_cast_vote(ctx: Context<CastVote>, vote_type: VoteType) -> Result<
         GovernanceError::ProposalNotInVotingState
     );
 
-    let direct_weight = user_token_account.amount;
+    let token_weight = match ctx.accounts.realm.power_source {
+        VotingPowerSource::StakedBalance => 0,
+        _ => user_token_account.amount,
+    };
+    let staked_weight = match ctx.accounts.realm.power_source {
+        VotingPowerSource::TokenBalance => 0,
+        _ => ctx
+            .accounts
+            .user_stake_account
+            .as_ref()
+            .map(|stake| stake.amount_staked)
+            .unwrap_or(0),
+    };
+    let direct_weight = token_weight + staked_weight;
     let delegated_weight = delegated_votes.map(|dv| dv.amount).unwrap_or(0);
     let total_weight = direct_weight + delegated_weight;
 
@@ -83,5 +99,13 @@ pub struct CastVote<'info> {
     )]
     pub delegated_votes: Option<Account<'info, DelegatedVotes>>,
 
+    // Owner and seeds tie the position to the voter and the Realm's staking pool
+    #[account(
+        seeds = [b"user_stake", realm.staking_pool.as_ref(), voter.key().as_ref()],
+        bump = user_stake_account.bump,
+        seeds::program = staking::ID,
+    )]
+    pub user_stake_account: Option<Account<'info, UserStakeAccount>>,
+
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::error::GovernanceError;
+use crate::events::VotingPowerConfigured;
+use crate::state::{Realm, VotingPowerSource};
+use staking::state::StakePool;
+
+pub fn process_configure_voting_power(
+    ctx: Context<ConfigureVotingPower>,
+    source: VotingPowerSource,
+) -> Result<()> {
+    // Staked voting power needs a pool to read positions from
+    let staking_pool = ctx
+        .accounts
+        .staking_pool
+        .as_ref()
+        .map_or(Pubkey::default(), |pool| pool.key());
+    require!(
+        source == VotingPowerSource::TokenBalance || staking_pool != Pubkey::default(),
+        GovernanceError::MissingStakingPool
+    );
+
+    let realm = &mut ctx.accounts.realm;
+    realm.power_source = source;
+    realm.staking_pool = staking_pool;
+
+    emit!(VotingPowerConfigured {
+        realm: realm.key(),
+        source,
+        staking_pool
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ConfigureVotingPower<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"realm", realm.authority.as_ref(), realm.name.as_bytes()],
+        bump = realm.bump,
+        constraint = realm.authority == authority.key() @ GovernanceError::Unauthorized
+    )]
+    pub realm: Account<'info, Realm>,
+    // Owner and seeds make it a real pool, staking the Realm's voting token
+    #[account(
+        seeds = [
+            b"stake_pool",
+            staking_pool.stake_mint.as_ref(),
+            staking_pool.reward_mint.as_ref(),
+            staking_pool.creator_seed()
+        ],
+        bump = staking_pool.bump,
+        seeds::program = staking::ID,
+        constraint = staking_pool.stake_mint == realm.voting_mint @ GovernanceError::StakingPoolMintMismatch
+    )]
+    pub staking_pool: Option<Account<'info, StakePool>>,
+}
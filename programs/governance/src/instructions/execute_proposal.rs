@@ -11,6 +11,8 @@ use crate::{
 };
 
 pub fn process_execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+    let realm_key = ctx.accounts.realm.key();
+    let (_, authority_bump) = Realm::governance_authority(&realm_key);
     let proposal = &mut ctx.accounts.proposal;
     let now = Clock::get()?.unix_timestamp;
 
@@ -41,12 +43,17 @@ pub fn process_execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
             data: ix.data.clone(),
         };
 
-        let seeds: &[&[&[u8]]] = &[&[
-            b"proposal",
-            proposal.realm.as_ref(),
-            proposal.proposer.as_ref(),
-            &[proposal.bump],
-        ]];
+        // The realm authority PDA lets proposals administer accounts owned by the realm,
+        // such as a staking pool whose authority was handed to governance
+        let seeds: &[&[&[u8]]] = &[
+            &[
+                b"proposal",
+                proposal.realm.as_ref(),
+                proposal.proposer.as_ref(),
+                &[proposal.bump],
+            ],
+            &[Realm::AUTHORITY_SEED, realm_key.as_ref(), &[authority_bump]],
+        ];
         invoke_signed(&ix, ctx.remaining_accounts, seeds)?;
     }
 
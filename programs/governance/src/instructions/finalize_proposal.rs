@@ -22,7 +22,7 @@ pub fn process_finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
     let total_votes = proposal.yes_votes + proposal.no_votes + proposal.abstain_votes;
     let participation = (total_votes as u128 * 10000) / (mint.supply as u128);
     let approval = if total_votes > 0 {
-        (proposal.yes_votes as u128) / (total_votes as u128)
+        (proposal.yes_votes as u128 * 10000) / (total_votes as u128)
     } else {
         0
     };
@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 
 use crate::error::GovernanceError;
 use crate::events::RealmInitiated;
-use crate::state::Realm;
+use crate::state::{Realm, VotingPowerSource};
 
 pub fn process_initialize_realm(
     ctx: Context<InitializeRealm>,
@@ -34,6 +34,8 @@ pub fn process_initialize_realm(
     realm.approval_threshold = approval_threshold;
     realm.proposal_count = 0;
     realm.bump = ctx.bumps.realm;
+    realm.power_source = VotingPowerSource::TokenBalance;
+    realm.staking_pool = Pubkey::default();
 
     emit!(RealmInitiated {
         realm: realm.key(),
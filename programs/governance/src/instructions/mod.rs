@@ -1,4 +1,5 @@
 pub mod cast_vote;
+pub mod configure_voting_power;
 pub mod create_proposal;
 pub mod delegate_votes;
 pub mod execute_proposal;
@@ -6,6 +7,7 @@ pub mod finalize_proposal;
 pub mod initialize_realm;
 
 pub use cast_vote::*;
+pub use configure_voting_power::*;
 pub use create_proposal::*;
 pub use delegate_votes::*;
 pub use execute_proposal::*;
@@ -5,7 +5,7 @@ pub mod events;
 pub mod instructions;
 pub mod state;
 
-use crate::state::{ProposalInstruction, ProposalType, VoteType};
+use crate::state::{ProposalInstruction, ProposalType, VoteType, VotingPowerSource};
 pub use instructions::*;
 
 declare_id!("Gover11111111111111111111111111111111111111");
@@ -36,6 +36,13 @@ pub mod governance {
         )
     }
 
+    pub fn configure_voting_power(
+        ctx: Context<ConfigureVotingPower>,
+        source: VotingPowerSource,
+    ) -> Result<()> {
+        process_configure_voting_power(ctx, source)
+    }
+
     pub fn create_proposal(
         ctx: Context<CreateProposal>,
         title: String,
@@ -16,6 +16,18 @@ pub struct Realm {
     pub approval_threshold: u64,             // % of yes votes required for approval
     pub proposal_count: u64,                 // Auto-incrementing counter for proposals
     pub bump: u8,                            // PDA bump
+    pub power_source: VotingPowerSource,     // What counts towards voting weight
+    pub staking_pool: Pubkey,                // Staking pool whose positions vote, if any
+}
+
+impl Realm {
+    /// Seed of the PDA that signs passed proposals' instructions for the Realm.
+    pub const AUTHORITY_SEED: &'static [u8] = b"realm_authority";
+
+    /// Governance-controlled address of a Realm, e.g. to hand a staking pool's authority to.
+    pub fn governance_authority(realm: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::AUTHORITY_SEED, realm.as_ref()], &crate::ID)
+    }
 }
 
 /// Proposals account defines a governance proposal.
@@ -82,6 +94,17 @@ pub enum ProposalStatus {
     Executed,
 }
 
+/// Balances counted as voting weight in a Realm.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum VotingPowerSource {
+    /// Voting mint tokens held in the voter's wallet.
+    TokenBalance,
+    /// Voting tokens staked in the Realm's staking pool.
+    StakedBalance,
+    /// Wallet balance plus staked balance.
+    TokenAndStakedBalance,
+}
+
 /// Type of vote cast on proposal.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub enum VoteType {
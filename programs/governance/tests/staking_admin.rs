@@ -0,0 +1,337 @@
+//! End to end: a staking pool handed to a Realm is administered by passed proposals.
+//!
+//! Both programs run natively on `native-runtime`.
+
+use anchor_lang::{
+    prelude::*, solana_program::instruction::Instruction, system_program, InstructionData,
+    ToAccountMetas,
+};
+use anchor_spl::token::spl_token;
+use governance::state::{
+    Proposal, ProposalAccountMeta, ProposalInstruction, ProposalStatus, ProposalType, Realm,
+    VoteType, VotingPowerSource,
+};
+use native_runtime::Ledger;
+use staking::state::{EarlyExitConfig, PoolEntry, PoolKind, PoolLimits, PoolRegistry, StakePool};
+
+fn governance_ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction::new_with_bytes(
+        governance::ID,
+        &data.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+fn staking_ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction::new_with_bytes(staking::ID, &data.data(), accounts.to_account_metas(None))
+}
+
+fn proposal_instruction(ix: Instruction) -> ProposalInstruction {
+    ProposalInstruction {
+        program_id: ix.program_id,
+        accounts: ix
+            .accounts
+            .into_iter()
+            .map(|meta| ProposalAccountMeta {
+                pubkey: meta.pubkey,
+                is_signer: meta.is_signer,
+                is_writeable: meta.is_writable,
+            })
+            .collect(),
+        data: ix.data,
+    }
+}
+
+#[test]
+fn passed_proposal_updates_pool_reward_rate() {
+    let mut ledger = Ledger::new();
+    ledger.add_program(governance::ID, governance::entry);
+
+    let admin = Pubkey::new_unique();
+    let voter = Pubkey::new_unique();
+    let voting_mint = Pubkey::new_unique();
+    let reward_mint = Pubkey::new_unique();
+    let voter_tokens = Pubkey::new_unique();
+    ledger.fund(admin);
+    ledger.fund(voter);
+    ledger.mint(voting_mint, admin, 1_000);
+    ledger.mint(reward_mint, admin, 0);
+    ledger.token_account(voter_tokens, voting_mint, voter, 600);
+
+    // The upgrade authority sets up the config, trusting the admin's pools
+    let upgrade_authority = Pubkey::new_unique();
+    ledger.fund(upgrade_authority);
+    let program_data =
+        ledger.add_upgradeable_program(staking::ID, staking::entry, upgrade_authority);
+    let (config, _) = Pubkey::find_program_address(&[b"config"], &staking::ID);
+    let (pool_registry, _) = Pubkey::find_program_address(&[b"pool_registry"], &staking::ID);
+    let initialize_config = |signer| {
+        staking_ix(
+            staking::accounts::InitializeConfig {
+                admin: signer,
+                config,
+                pool_registry,
+                program: staking::ID,
+                program_data,
+                system_program: system_program::ID,
+            },
+            staking::instruction::InitializeConfig {
+                pool_creators: vec![admin],
+                creation_fee: None,
+                fee_recipient: upgrade_authority,
+            },
+        )
+    };
+    assert!(ledger.process(initialize_config(admin)).is_err());
+    ledger
+        .process(initialize_config(upgrade_authority))
+        .unwrap();
+
+    // A hot-wallet admin creates the pool, staking the governance token
+    let (stake_pool, _) = Pubkey::find_program_address(
+        &[b"stake_pool", voting_mint.as_ref(), reward_mint.as_ref()],
+        &staking::ID,
+    );
+    let (pool_entry, _) =
+        Pubkey::find_program_address(&[b"pool_entry", &0u64.to_le_bytes()], &staking::ID);
+    let (pool_stake_vault, _) = Pubkey::find_program_address(
+        &[b"stake_vault", stake_pool.as_ref(), voting_mint.as_ref()],
+        &staking::ID,
+    );
+    let (pool_reward_vault, _) = Pubkey::find_program_address(
+        &[b"reward_vault", stake_pool.as_ref(), reward_mint.as_ref()],
+        &staking::ID,
+    );
+    ledger
+        .process(staking_ix(
+            staking::accounts::InitializePool {
+                authority: admin,
+                config,
+                pool_registry,
+                pool_entry,
+                fee_recipient: upgrade_authority,
+                stake_mint: voting_mint,
+                reward_mint,
+                stake_pool,
+                pool_stake_vault,
+                pool_reward_vault,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            staking::instruction::InitializePool {
+                reward_rate: 100,
+                minimum_stake_duration: 0,
+                early_exit: EarlyExitConfig {
+                    penalty_bps: 0,
+                    linear_decay: false,
+                    treasury: None,
+                },
+                limits: PoolLimits {
+                    max_total_staked: None,
+                    max_per_user: None,
+                    min_stake_amount: 0,
+                },
+                reward_vesting_duration: 0,
+            },
+        ))
+        .unwrap();
+
+    assert_eq!(ledger.get::<PoolRegistry>(&pool_registry).pool_count, 1);
+    let entry = ledger.get::<PoolEntry>(&pool_entry);
+    assert_eq!((entry.pool, entry.index), (stake_pool, 0));
+    assert!(entry.kind == PoolKind::Token && entry.official);
+
+    let name = "dao".to_string();
+    let (realm, _) = Pubkey::find_program_address(
+        &[b"realm", admin.as_ref(), name.as_bytes()],
+        &governance::ID,
+    );
+    ledger
+        .process(governance_ix(
+            governance::accounts::InitializeRealm {
+                authority: admin,
+                realm,
+                system_program: system_program::ID,
+            },
+            governance::instruction::InitializeRealm {
+                name,
+                voting_mint,
+                min_tokens_to_create_proposal: 1,
+                voting_duration: 3_600,
+                execution_delay: 600,
+                quorum_threshold: 2_000,
+                approval_threshold: 5_000,
+            },
+        ))
+        .unwrap();
+
+    // Staked voting power only reads pools staking the voting token
+    let configure_voting_power = |source, staking_pool| {
+        governance_ix(
+            governance::accounts::ConfigureVotingPower {
+                authority: admin,
+                realm,
+                staking_pool,
+            },
+            governance::instruction::ConfigureVotingPower { source },
+        )
+    };
+    let mut other_pool = ledger.get::<StakePool>(&stake_pool);
+    other_pool.stake_mint = reward_mint;
+    other_pool.reward_mint = voting_mint;
+    let (other_pool_key, other_bump) = Pubkey::find_program_address(
+        &[b"stake_pool", reward_mint.as_ref(), voting_mint.as_ref()],
+        &staking::ID,
+    );
+    other_pool.bump = other_bump;
+    let mut data = Vec::new();
+    other_pool.try_serialize(&mut data).unwrap();
+    ledger.set(other_pool_key, staking::ID, 1_000_000_000, data, false);
+    assert!(ledger
+        .process(configure_voting_power(
+            VotingPowerSource::StakedBalance,
+            Some(other_pool_key)
+        ))
+        .is_err());
+    ledger
+        .process(configure_voting_power(
+            VotingPowerSource::StakedBalance,
+            Some(stake_pool),
+        ))
+        .unwrap();
+    assert_eq!(ledger.get::<Realm>(&realm).staking_pool, stake_pool);
+    ledger
+        .process(configure_voting_power(
+            VotingPowerSource::TokenBalance,
+            None,
+        ))
+        .unwrap();
+
+    // The admin hands the pool to the Realm, which accepts it by proposal
+    let (realm_authority, _) = Realm::governance_authority(&realm);
+    ledger
+        .process(staking_ix(
+            staking::accounts::ProposeAuthority {
+                authority: admin,
+                stake_pool,
+            },
+            staking::instruction::ProposeAuthority {
+                new_authority: realm_authority,
+            },
+        ))
+        .unwrap();
+
+    let accept_authority = staking_ix(
+        staking::accounts::AcceptAuthority {
+            new_authority: realm_authority,
+            stake_pool,
+        },
+        staking::instruction::AcceptAuthority {},
+    );
+    let update_pool = staking_ix(
+        staking::accounts::UpdatePool {
+            authority: realm_authority,
+            stake_pool,
+        },
+        staking::instruction::UpdatePool {
+            new_reward_rate: Some(250),
+            new_min_duration: None,
+            new_early_exit: None,
+            new_limits: None,
+            new_vesting_duration: None,
+        },
+    );
+
+    let (proposal, _) = Pubkey::find_program_address(
+        &[b"proposal", realm.as_ref(), voter.as_ref()],
+        &governance::ID,
+    );
+    ledger
+        .process(governance_ix(
+            governance::accounts::CreateProposal {
+                proposer: voter,
+                realm,
+                proposal,
+                user_token_account: voter_tokens,
+                system_program: system_program::ID,
+            },
+            governance::instruction::CreateProposal {
+                title: "Raise staking emissions".to_string(),
+                ipfs_hash: [0; 46],
+                proposal_type: ProposalType::Executable,
+                execution_instruction: Some(vec![
+                    proposal_instruction(accept_authority),
+                    proposal_instruction(update_pool.clone()),
+                ]),
+            },
+        ))
+        .unwrap();
+
+    let (vote_record, _) = Pubkey::find_program_address(
+        &[b"vote", proposal.as_ref(), voter.as_ref()],
+        &governance::ID,
+    );
+    ledger
+        .process(governance_ix(
+            governance::accounts::CastVote {
+                voter,
+                realm,
+                proposal,
+                vote_record,
+                user_token_account: voter_tokens,
+                delegated_votes: None,
+                user_stake_account: None,
+                system_program: system_program::ID,
+            },
+            governance::instruction::CastVote {
+                vote_type: VoteType::Yes,
+            },
+        ))
+        .unwrap();
+
+    ledger.advance_clock(3_601);
+    ledger
+        .process(governance_ix(
+            governance::accounts::FinalizeProposal {
+                authority: voter,
+                realm,
+                proposal,
+                voting_mint,
+            },
+            governance::instruction::FinalizeProposal {},
+        ))
+        .unwrap();
+    assert!(ledger.get::<Proposal>(&proposal).status == ProposalStatus::Passed);
+
+    // The realm authority only exists as a signer of passed proposals
+    let mut execute = governance_ix(
+        governance::accounts::ExecuteProposal {
+            authority: voter,
+            realm,
+            proposal,
+        },
+        governance::instruction::ExecuteProposal {},
+    );
+    execute.accounts.extend([
+        AccountMeta::new_readonly(staking::ID, false),
+        AccountMeta::new_readonly(realm_authority, false),
+        AccountMeta::new(stake_pool, false),
+    ]);
+
+    ledger.advance_clock(601);
+    ledger.process(execute).unwrap();
+
+    let pool = ledger.get::<StakePool>(&stake_pool);
+    assert_eq!(pool.authority, realm_authority);
+    assert_eq!(pool.reward_rate, 250);
+    assert!(ledger.get::<Proposal>(&proposal).status == ProposalStatus::Executed);
+
+    // Neither the old hot wallet nor an unsigned realm authority can change it directly
+    let mut by_admin = update_pool.clone();
+    by_admin.accounts[0] = AccountMeta::new_readonly(admin, true);
+    assert!(ledger.process(by_admin).is_err());
+    let mut unsigned = update_pool;
+    unsigned.accounts[0].is_signer = false;
+    assert!(ledger.process(unsigned).is_err());
+    assert_eq!(ledger.get::<StakePool>(&stake_pool).reward_rate, 250);
+}
@@ -0,0 +1,240 @@
+//! Staking positions in the Realm's pool count as voting power.
+
+use anchor_lang::{
+    prelude::*, solana_program::instruction::Instruction, system_program, InstructionData, Space,
+    ToAccountMetas,
+};
+use anchor_spl::token::spl_token;
+use governance::state::{Proposal, ProposalType, VoteType, VotingPowerSource};
+use native_runtime::Ledger;
+use staking::state::StakePool;
+
+fn governance_ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction::new_with_bytes(
+        governance::ID,
+        &data.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+fn staking_ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction::new_with_bytes(staking::ID, &data.data(), accounts.to_account_metas(None))
+}
+
+/// A Realm voting with `voting_mint`, the staking pool for that mint and an
+/// open proposal to vote on.
+struct Dao {
+    ledger: Ledger,
+    admin: Pubkey,
+    voting_mint: Pubkey,
+    stake_pool: Pubkey,
+    pool_stake_vault: Pubkey,
+    realm: Pubkey,
+    proposal: Pubkey,
+}
+
+/// A funded wallet with a voting token account.
+struct Voter {
+    wallet: Pubkey,
+    tokens: Pubkey,
+}
+
+impl Dao {
+    fn new() -> Self {
+        let mut ledger = Ledger::new();
+        ledger.add_program(governance::ID, governance::entry);
+        ledger.add_program(staking::ID, staking::entry);
+        let [admin, voting_mint, proposer, proposer_tokens] = [(); 4].map(|_| Pubkey::new_unique());
+        ledger.fund(admin);
+        ledger.fund(proposer);
+        ledger.mint(voting_mint, admin, 1_000_000);
+        ledger.token_account(proposer_tokens, voting_mint, proposer, 1);
+
+        let (stake_pool, bump) = Pubkey::find_program_address(
+            &[b"stake_pool", voting_mint.as_ref(), voting_mint.as_ref()],
+            &staking::ID,
+        );
+        let pool = StakePool {
+            authority: admin,
+            stake_mint: voting_mint,
+            reward_mint: voting_mint,
+            last_updated: ledger.now(),
+            bump,
+            version: 1,
+            ..StakePool::default()
+        };
+        ledger.set_anchor(stake_pool, staking::ID, &pool, 8 + StakePool::INIT_SPACE);
+        let (pool_stake_vault, _) = Pubkey::find_program_address(
+            &[b"stake_vault", stake_pool.as_ref(), voting_mint.as_ref()],
+            &staking::ID,
+        );
+        ledger.token_account(pool_stake_vault, voting_mint, stake_pool, 0);
+
+        let name = "dao".to_string();
+        let (realm, _) = Pubkey::find_program_address(
+            &[b"realm", admin.as_ref(), name.as_bytes()],
+            &governance::ID,
+        );
+        ledger
+            .process(governance_ix(
+                governance::accounts::InitializeRealm {
+                    authority: admin,
+                    realm,
+                    system_program: system_program::ID,
+                },
+                governance::instruction::InitializeRealm {
+                    name,
+                    voting_mint,
+                    min_tokens_to_create_proposal: 1,
+                    voting_duration: 3_600,
+                    execution_delay: 600,
+                    quorum_threshold: 2_000,
+                    approval_threshold: 5_000,
+                },
+            ))
+            .unwrap();
+        let (proposal, _) = Pubkey::find_program_address(
+            &[b"proposal", realm.as_ref(), proposer.as_ref()],
+            &governance::ID,
+        );
+        ledger
+            .process(governance_ix(
+                governance::accounts::CreateProposal {
+                    proposer,
+                    realm,
+                    proposal,
+                    user_token_account: proposer_tokens,
+                    system_program: system_program::ID,
+                },
+                governance::instruction::CreateProposal {
+                    title: "Signal".to_string(),
+                    ipfs_hash: [0; 46],
+                    proposal_type: ProposalType::Text,
+                    execution_instruction: None,
+                },
+            ))
+            .unwrap();
+        Self {
+            ledger,
+            admin,
+            voting_mint,
+            stake_pool,
+            pool_stake_vault,
+            realm,
+            proposal,
+        }
+    }
+
+    /// A voter holding `held` tokens, of which `staked` are staked in the pool.
+    fn voter(&mut self, held: u64, staked: u64) -> Voter {
+        let [wallet, tokens] = [(); 2].map(|_| Pubkey::new_unique());
+        self.ledger.fund(wallet);
+        self.ledger
+            .token_account(tokens, self.voting_mint, wallet, held);
+        if staked > 0 {
+            self.ledger
+                .process(staking_ix(
+                    staking::accounts::Stake {
+                        user: wallet,
+                        stake_pool: self.stake_pool,
+                        user_stake_account: self.user_stake(wallet),
+                        user_token_account: tokens,
+                        pool_stake_vault: self.pool_stake_vault,
+                        stake_mint: self.voting_mint,
+                        token_program: spl_token::ID,
+                        system_program: system_program::ID,
+                    },
+                    staking::instruction::Stake {
+                        amount: staked,
+                        referrer: None,
+                    },
+                ))
+                .unwrap();
+        }
+        Voter { wallet, tokens }
+    }
+
+    fn user_stake(&self, wallet: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"user_stake", self.stake_pool.as_ref(), wallet.as_ref()],
+            &staking::ID,
+        )
+        .0
+    }
+
+    fn configure(&mut self, source: VotingPowerSource) {
+        self.ledger
+            .process(governance_ix(
+                governance::accounts::ConfigureVotingPower {
+                    authority: self.admin,
+                    realm: self.realm,
+                    staking_pool: Some(self.stake_pool),
+                },
+                governance::instruction::ConfigureVotingPower { source },
+            ))
+            .unwrap();
+    }
+
+    /// `voter` votes yes, counting the stake in `user_stake_account`.
+    fn vote(&self, voter: &Voter, user_stake_account: Option<Pubkey>) -> Instruction {
+        let (vote_record, _) = Pubkey::find_program_address(
+            &[b"vote", self.proposal.as_ref(), voter.wallet.as_ref()],
+            &governance::ID,
+        );
+        governance_ix(
+            governance::accounts::CastVote {
+                voter: voter.wallet,
+                realm: self.realm,
+                proposal: self.proposal,
+                vote_record,
+                user_token_account: voter.tokens,
+                delegated_votes: None,
+                user_stake_account,
+                system_program: system_program::ID,
+            },
+            governance::instruction::CastVote {
+                vote_type: VoteType::Yes,
+            },
+        )
+    }
+
+    fn yes_votes(&self) -> u64 {
+        self.ledger.get::<Proposal>(&self.proposal).yes_votes
+    }
+}
+
+#[test]
+fn staked_tokens_vote_alongside_wallet_balances() {
+    let mut dao = Dao::new();
+    let alice = dao.voter(600, 400);
+    dao.configure(VotingPowerSource::TokenAndStakedBalance);
+    let position = dao.user_stake(alice.wallet);
+    dao.ledger
+        .process(dao.vote(&alice, Some(position)))
+        .unwrap();
+    assert_eq!(dao.yes_votes(), 600);
+
+    // Counting only stake, the wallet balance no longer votes
+    let bob = dao.voter(500, 300);
+    dao.configure(VotingPowerSource::StakedBalance);
+    let position = dao.user_stake(bob.wallet);
+    dao.ledger.process(dao.vote(&bob, Some(position))).unwrap();
+    assert_eq!(dao.yes_votes(), 900);
+}
+
+#[test]
+fn voters_cannot_count_stake_that_is_not_theirs() {
+    let mut dao = Dao::new();
+    let alice = dao.voter(600, 600);
+    let bob = dao.voter(100, 0);
+    dao.configure(VotingPowerSource::StakedBalance);
+
+    let alices_position = dao.user_stake(alice.wallet);
+    assert!(dao
+        .ledger
+        .process(dao.vote(&bob, Some(alices_position)))
+        .is_err());
+    // Without a stake of his own Bob has no voting power here
+    assert!(dao.ledger.process(dao.vote(&bob, None)).is_err());
+    assert_eq!(dao.yes_votes(), 0);
+}
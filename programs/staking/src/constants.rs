@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+/// Fixed-point scale of `reward_per_token_stored`.
+#[constant]
+pub const PRECISION: u64 = reward_math::PRECISION;
+
+#[constant]
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Deposits tracked separately per user before new ones are folded into an existing lot.
+#[constant]
+pub const MAX_STAKE_LOTS: usize = 10;
+
+/// Balance changes kept per stake account and per pool for `get_stake_at`.
+#[constant]
+pub const MAX_STAKE_CHECKPOINTS: usize = 32;
+
+#[constant]
+pub const MAX_VESTING_ENTRIES: usize = 8;
+
+#[constant]
+pub const MAX_REWARD_SEGMENTS: usize = 8;
+
+/// Creators the `Config` allowlist can hold.
+#[constant]
+pub const MAX_POOL_CREATORS: usize = 16;
+
+/// Highest boost multiplier, 5x.
+#[constant]
+pub const MAX_BOOST_BPS: u16 = 50_000;
+
+#[constant]
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+/// Longest hard lock a `stake_for` funder can set, 4 years.
+#[constant]
+pub const MAX_LOCK_DURATION: i64 = 4 * SECONDS_PER_YEAR as i64;
+
+/// Metaplex Token Metadata program, owner of the metadata accounts that name
+/// an NFT's verified collection.
+pub const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Layout version of new and migrated `StakePool` accounts.
+#[constant]
+pub const STAKE_POOL_VERSION: u8 = 1;
+
+/// Layout version of new and migrated `UserStakeAccount` accounts.
+#[constant]
+pub const USER_STAKE_VERSION: u8 = 1;
+
+/// Account sizes of the original layouts, from before the version byte was
+/// added. Such accounts hold no version and must go through `migrate_pool` /
+/// `migrate_user_stake`.
+pub const UNVERSIONED_STAKE_POOL_LEN: usize = 8 + 137;
+pub const UNVERSIONED_USER_STAKE_LEN: usize = 8 + 97;
@@ -12,4 +12,120 @@ pub enum StakingError {
     MinimumStakeDurationNotMet,
     #[msg("No rewards to claim")]
     NoRewardsToClaim,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Penalty must not exceed 10000 bps")]
+    InvalidPenalty,
+    #[msg("Penalty redistribution requires the stake and reward mints to match")]
+    PenaltyRedistributionUnsupported,
+    #[msg("Penalty destination account is missing or invalid")]
+    InvalidPenaltyAccount,
+    #[msg("Compounding requires the stake and reward mints to match")]
+    CompoundUnsupported,
+    #[msg("Rewards of this pool vest and cannot be compounded")]
+    CompoundWhileVesting,
+    #[msg("User has not opted in to auto-compounding")]
+    AutoCompoundNotEnabled,
+    #[msg("Receipt mint or receipt token account is missing or invalid")]
+    InvalidReceiptAccount,
+    #[msg("Pool already has a receipt mint")]
+    ReceiptMintUnavailable,
+    #[msg("Stake pool is paused")]
+    PoolPaused,
+    #[msg("Nothing staked")]
+    NothingStaked,
+    #[msg("Stake amount is below the pool minimum")]
+    StakeBelowMinimum,
+    #[msg("Stake would exceed the per-user limit")]
+    UserStakeLimitExceeded,
+    #[msg("Stake would exceed the pool capacity")]
+    PoolCapacityExceeded,
+    #[msg("Per-user cap must not exceed the pool cap, nor the minimum stake either cap")]
+    InvalidPoolLimits,
+    #[msg("Reward schedule must be in the future, strictly ordered and within the segment limit")]
+    InvalidRewardSchedule,
+    #[msg("Stake account still holds stake or rewards")]
+    StakeAccountNotEmpty,
+    #[msg("Slashing is not configured for this pool")]
+    SlashingNotConfigured,
+    #[msg("Slash must be between 1 and 10000 bps")]
+    InvalidSlashAmount,
+    #[msg("Amount exceeds the reward tokens not owed to stakers")]
+    InsufficientExcessRewards,
+    #[msg("Token account cannot be recovered")]
+    TokenNotRecoverable,
+    #[msg("Vesting duration cannot be negative")]
+    InvalidVestingDuration,
+    #[msg("No vested rewards to withdraw")]
+    NothingVested,
+    #[msg("Stake is locked until its unlock time")]
+    StakeLocked,
+    #[msg("Lock must end within the maximum lock duration")]
+    InvalidLockTime,
+    #[msg("Every stake lot is taken")]
+    StakeLotsFull,
+    #[msg("Mint has an unsupported Token-2022 extension")]
+    UnsupportedMintExtension,
+    #[msg("Account already uses the current layout")]
+    AccountUpToDate,
+    #[msg("Account must be migrated to the current layout first")]
+    AccountNotMigrated,
+    #[msg("Reward vault is missing or invalid")]
+    InvalidRewardVault,
+    #[msg("Stake account belongs to a different pool")]
+    StakePoolMismatch,
+    #[msg("Points pools have no reward token")]
+    PointsPoolHasNoRewards,
+    #[msg("No stake checkpoint is held for that slot")]
+    CheckpointUnavailable,
+    #[msg("Boost multiplier must be between 1x and 5x")]
+    InvalidBoostMultiplier,
+    #[msg("Boost account does not match the one chosen by the user")]
+    InvalidBoostAccount,
+    #[msg("Boost claim is missing or does not match the boost")]
+    InvalidBoostClaim,
+    #[msg("Boost must be released through refresh_boost first")]
+    BoostStillClaimed,
+    #[msg("Not supported on NFT pools")]
+    NftPoolUnsupported,
+    #[msg("Only supported on NFT pools")]
+    NotAnNftPool,
+    #[msg("Mint is not a Token-2022 collection")]
+    NotACollection,
+    #[msg("Mint is not an NFT of the pool's collection")]
+    NotInCollection,
+    #[msg("NFT weight must be greater than zero")]
+    InvalidNftWeight,
+    #[msg("Too many pool creators")]
+    TooManyPoolCreators,
+    #[msg("Creator is not allowed to open pools")]
+    PoolCreationNotAllowed,
+    #[msg("Fee recipient does not match the config")]
+    InvalidFeeRecipient,
+    #[msg("Stakers cannot refer themselves")]
+    SelfReferral,
+    #[msg("Referral bonus must be at most 10000 bps")]
+    InvalidReferralBps,
+    #[msg("No referral rewards to claim")]
+    NoReferralRewards,
+    #[msg("Referral rewards must be claimed before closing")]
+    ReferralRewardsUnclaimed,
+    #[msg("Referral budget is not covered by the reward vault")]
+    ReferralBudgetUnfunded,
+    #[msg("Vesting referral rewards need the referrer's stake account")]
+    ReferrerStakeAccountMissing,
+    #[msg("Receipt pools must restake rewards in the stake mint, without a minimum duration or vesting")]
+    ReceiptPoolTermsUnsupported,
+    #[msg("Tokens can only be locked for the pool's token boost")]
+    BoostNotLockable,
+    #[msg("Locked boost tokens must be unlocked first")]
+    BoostStillLocked,
+    #[msg("Pool is already in the registry")]
+    PoolAlreadyRegistered,
+}
+
+impl From<reward_math::MathError> for StakingError {
+    fn from(_: reward_math::MathError) -> Self {
+        StakingError::MathOverflow
+    }
 }
@@ -0,0 +1,188 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{EarlyExitConfig, PoolLimits};
+
+#[event]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub stake_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub reward_rate: u64,
+    pub min_stake_duration: i64,
+}
+
+#[event]
+pub struct Staked {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    /// Wallet that paid for the stake, `user` unless staked through `stake_for`.
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub user_total_staked: u64,
+    pub pool_total_staked: u64,
+    pub reward_per_token_stored: u64,
+}
+
+#[event]
+pub struct ReferralRewardsClaimed {
+    pub pool: Pubkey,
+    pub referrer: Pubkey,
+    /// Staker whose rewards earned the bonus.
+    pub user: Pubkey,
+    pub amount: u64,
+    /// The bonus went into the referrer's vesting schedule instead of their wallet.
+    pub vested: bool,
+}
+
+#[event]
+pub struct Unstaked {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub penalty: u64,
+    pub user_total_staked: u64,
+    pub pool_total_staked: u64,
+    pub reward_per_token_stored: u64,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    /// Rewards went into the user's vesting schedule instead of their wallet.
+    pub vested: bool,
+    pub reward_per_token_stored: u64,
+}
+
+#[event]
+pub struct PoolUpdated {
+    pub pool: Pubkey,
+    pub reward_rate: u64,
+    pub min_stake_duration: i64,
+    pub early_exit: EarlyExitConfig,
+    pub limits: PoolLimits,
+    pub reward_vesting_duration: i64,
+    pub pool_total_staked: u64,
+    pub reward_per_token_stored: u64,
+}
+
+#[event]
+pub struct VestedRewardsWithdrawn {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RewardsCompounded {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub user_total_staked: u64,
+    pub pool_total_staked: u64,
+    pub reward_per_token_stored: u64,
+}
+
+#[event]
+pub struct LiquidStaked {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub receipts: u64,
+    /// Stake backing all receipts, after the deposit.
+    pub receipt_staked: u64,
+    pub receipt_supply: u64,
+    pub pool_total_staked: u64,
+}
+
+#[event]
+pub struct LiquidUnstaked {
+    pub pool: Pubkey,
+    pub holder: Pubkey,
+    pub receipts: u64,
+    pub amount: u64,
+    /// Stake backing all receipts, after the redemption.
+    pub receipt_staked: u64,
+    pub receipt_supply: u64,
+    pub pool_total_staked: u64,
+}
+
+#[event]
+pub struct EmergencyWithdrawn {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub forfeited_rewards: u64,
+    pub pool_total_staked: u64,
+    pub reward_per_token_stored: u64,
+}
+
+#[event]
+pub struct StakeSlashed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub reason_code: u16,
+    pub user_total_staked: u64,
+    pub pool_total_staked: u64,
+    pub reward_per_token_stored: u64,
+}
+
+#[event]
+pub struct NftStaked {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub weight: u64,
+    pub user_total_staked: u64,
+    pub pool_total_staked: u64,
+    pub reward_per_token_stored: u64,
+}
+
+#[event]
+pub struct NftUnstaked {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub weight: u64,
+    pub user_total_staked: u64,
+    pub pool_total_staked: u64,
+    pub reward_per_token_stored: u64,
+}
+
+#[event]
+pub struct BoostRefreshed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub boost_bps: u16,
+    pub effective_stake: u64,
+    pub pool_total_effective_stake: u64,
+}
+
+#[event]
+pub struct BoostLocked {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub user_boost_locked: u64,
+    pub boost_bps: u16,
+}
+
+#[event]
+pub struct BoostUnlocked {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub user_boost_locked: u64,
+    pub boost_bps: u16,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    /// 0 for accounts created before versioning.
+    pub from_version: u8,
+    pub to_version: u8,
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::StakingError, state::StakePool};
+
+pub fn process_accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let pool = &mut ctx.accounts.stake_pool;
+    pool.authority = ctx.accounts.new_authority.key();
+    pool.pending_authority = None;
+
+    msg!("Pool authority transferred to: {}", pool.authority);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        constraint = stake_pool.pending_authority == Some(new_authority.key()) @ StakingError::Unauthorized
+    )]
+    pub new_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
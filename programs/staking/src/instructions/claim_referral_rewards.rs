@@ -0,0 +1,130 @@
+use crate::error::StakingError;
+use crate::events::ReferralRewardsClaimed;
+use crate::state::{creator_seed, StakePool, UserStakeAccount};
+use crate::utility::{
+    accrue_user_rewards, add_vesting_entry, open_stake_account, release_owed_rewards,
+    update_pool_rewards,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+/// Pays the referrer the bonus accrued on one referred staker's rewards. In
+/// pools whose rewards vest, the bonus vests too, in the referrer's own stake
+/// account, and is withdrawn through `withdraw_vested`.
+pub fn process_claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    update_pool_rewards(&mut ctx.accounts.stake_pool, current_time)?;
+    accrue_user_rewards(
+        &mut ctx.accounts.user_stake_account,
+        &mut ctx.accounts.stake_pool,
+    )?;
+
+    let amount = ctx.accounts.user_stake_account.referral_owed;
+    require!(amount > 0, StakingError::NoReferralRewards);
+
+    let vesting_duration = ctx.accounts.stake_pool.reward_vesting_duration;
+    if vesting_duration > 0 {
+        // tokens stay in the vault, and stay owed, until withdraw_vested
+        let referrer_stake = ctx
+            .accounts
+            .referrer_stake_account
+            .as_mut()
+            .ok_or(StakingError::ReferrerStakeAccountMissing)?;
+        if referrer_stake.user == Pubkey::default() {
+            let bump = ctx
+                .bumps
+                .referrer_stake_account
+                .ok_or(StakingError::ReferrerStakeAccountMissing)?;
+            open_stake_account(
+                &ctx.accounts.stake_pool,
+                referrer_stake,
+                ctx.accounts.referrer.key(),
+                bump,
+            );
+        }
+        add_vesting_entry(referrer_stake, amount, current_time, vesting_duration)?;
+    } else {
+        let stake_mint_key = ctx.accounts.stake_pool.stake_mint.key();
+        let reward_mint_key = ctx.accounts.stake_pool.reward_mint.key();
+        let community_creator = ctx.accounts.stake_pool.community_creator;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"stake_pool",
+            stake_mint_key.as_ref(),
+            reward_mint_key.as_ref(),
+            creator_seed(&community_creator),
+            &[ctx.accounts.stake_pool.bump],
+        ]];
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.pool_reward_vault.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.referrer_reward_account.to_account_info(),
+                authority: ctx.accounts.stake_pool.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(cpi_context, amount, ctx.accounts.reward_mint.decimals)?;
+        release_owed_rewards(&mut ctx.accounts.stake_pool, amount);
+    }
+    ctx.accounts.user_stake_account.referral_owed = 0;
+
+    emit!(ReferralRewardsClaimed {
+        pool: ctx.accounts.stake_pool.key(),
+        referrer: ctx.accounts.referrer.key(),
+        user: ctx.accounts.user_stake_account.user,
+        amount,
+        vested: vesting_duration > 0,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = !stake_pool.paused @ StakingError::PoolPaused,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user_stake_account.user.as_ref()],
+        bump = user_stake_account.bump,
+        constraint = user_stake_account.referrer == Some(referrer.key()) @ StakingError::Unauthorized,
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+    /// Receives the bonus as a vesting entry when the pool's rewards vest.
+    #[account(
+        init_if_needed,
+        payer = referrer,
+        space = 8 + UserStakeAccount::INIT_SPACE,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), referrer.key().as_ref()],
+        bump,
+    )]
+    pub referrer_stake_account: Option<Account<'info, UserStakeAccount>>,
+    #[account(
+        mut,
+        constraint = referrer_reward_account.owner == referrer.key(),
+        constraint = referrer_reward_account.mint == stake_pool.reward_mint,
+    )]
+    pub referrer_reward_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"reward_vault", stake_pool.key().as_ref(), stake_pool.reward_mint.as_ref()],
+        bump
+    )]
+    pub pool_reward_vault: InterfaceAccount<'info, TokenAccount>,
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
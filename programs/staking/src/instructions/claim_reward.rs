@@ -1,6 +1,9 @@
 use crate::error::StakingError;
-use crate::state::{StakePool, UserStakeAccount};
-use crate::utility::{calculate_pending_rewards, update_pool_rewards};
+use crate::events::RewardsClaimed;
+use crate::state::{creator_seed, StakePool, UserStakeAccount};
+use crate::utility::{
+    accrue_user_rewards, add_vesting_entry, release_owed_rewards, update_pool_rewards,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 
@@ -20,39 +23,52 @@ pub fn process_claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
     }
 
     // calculate total rewards to claim
-    let total_rewards = calculate_pending_rewards(user_stake, &ctx.accounts.stake_pool)?
-        .checked_add(user_stake.pending_rewards)
-        .unwrap();
+    accrue_user_rewards(user_stake, &mut ctx.accounts.stake_pool)?;
+    let total_rewards = user_stake.pending_rewards;
 
     require!(total_rewards > 0, StakingError::NoRewardsToClaim);
 
-    let stake_mint_key = ctx.accounts.stake_pool.stake_mint.key();
-    let reward_mint_key = ctx.accounts.stake_pool.reward_mint.key();
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        b"stake_pool",
-        stake_mint_key.as_ref(),
-        reward_mint_key.as_ref(),
-        &[ctx.accounts.stake_pool.bump],
-    ]];
-    let cpi_context = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        TransferChecked {
-            from: ctx.accounts.pool_reward_vault.to_account_info(),
-            mint: ctx.accounts.reward_mint.to_account_info(),
-            to: ctx.accounts.user_reward_account.to_account_info(),
-            authority: ctx.accounts.stake_pool.to_account_info(),
-        },
-        signer_seeds,
-    );
-    token_interface::transfer_checked(
-        cpi_context,
-        total_rewards,
-        ctx.accounts.reward_mint.decimals,
-    )?;
+    let vesting_duration = ctx.accounts.stake_pool.reward_vesting_duration;
+    if vesting_duration > 0 {
+        // tokens stay in the vault, and stay owed, until withdraw_vested
+        add_vesting_entry(user_stake, total_rewards, current_time, vesting_duration)?;
+    } else {
+        let stake_mint_key = ctx.accounts.stake_pool.stake_mint.key();
+        let reward_mint_key = ctx.accounts.stake_pool.reward_mint.key();
+        let community_creator = ctx.accounts.stake_pool.community_creator;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"stake_pool",
+            stake_mint_key.as_ref(),
+            reward_mint_key.as_ref(),
+            creator_seed(&community_creator),
+            &[ctx.accounts.stake_pool.bump],
+        ]];
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.pool_reward_vault.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.user_reward_account.to_account_info(),
+                authority: ctx.accounts.stake_pool.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(
+            cpi_context,
+            total_rewards,
+            ctx.accounts.reward_mint.decimals,
+        )?;
+        release_owed_rewards(&mut ctx.accounts.stake_pool, total_rewards);
+    }
     user_stake.pending_rewards = 0;
-    user_stake.reward_per_token_paid = ctx.accounts.stake_pool.reward_per_token_stored;
 
-    msg!("Claimed {} reward tokens", total_rewards);
+    emit!(RewardsClaimed {
+        pool: ctx.accounts.stake_pool.key(),
+        user: user_stake.user,
+        amount: total_rewards,
+        vested: vesting_duration > 0,
+        reward_per_token_stored: ctx.accounts.stake_pool.reward_per_token_stored,
+    });
 
     Ok(())
 }
@@ -63,8 +79,14 @@ pub struct ClaimRewards<'info> {
     pub user: Signer<'info>,
     #[account(
         mut,
-        seeds = [b"stake_pool", stake_pool.stake_mint.as_ref(), stake_pool.reward_mint.as_ref()],
-        bump = stake_pool.bump
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = !stake_pool.paused @ StakingError::PoolPaused,
     )]
     pub stake_pool: Account<'info, StakePool>,
     #[account(
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::error::StakingError;
+use crate::state::{StakePool, UserStakeAccount};
+
+pub fn process_close_stake_account(ctx: Context<CloseStakeAccount>) -> Result<()> {
+    msg!("Closed stake account {}", ctx.accounts.user_stake_account.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseStakeAccount<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user.key().as_ref()],
+        bump = user_stake_account.bump,
+        constraint = user_stake_account.amount_staked == 0
+            && user_stake_account.pending_rewards == 0
+            && user_stake_account.vesting.is_empty() @ StakingError::StakeAccountNotEmpty,
+        constraint = user_stake_account.boost_claim.is_none() @ StakingError::BoostStillClaimed,
+        constraint = user_stake_account.boost_locked == 0 @ StakingError::BoostStillLocked,
+        // the referrer's bonus is theirs, so it has to be claimed first
+        constraint = user_stake_account.referral_owed == 0 @ StakingError::ReferralRewardsUnclaimed,
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+}
@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::StakingError;
+use crate::events::RewardsCompounded;
+use crate::state::{creator_seed, StakePool, UserStakeAccount};
+use crate::utility::{
+    accrue_user_rewards, add_stake_lot, received_amount, record_stake_checkpoints,
+    release_owed_rewards, update_effective_stake, update_pool_rewards,
+};
+
+/// Restakes a user's pending rewards in pools where the stake and reward
+/// mints match. The user can always compound; anyone else only after the
+/// user has opted in through `set_auto_compound`. Compounded rewards start a
+/// new stake lot, which may take the user and the pool past their deposit
+/// limits. Pools that vest rewards cannot compound, as unstaking would
+/// release the rewards before they vest.
+pub fn process_compound(ctx: Context<Compound>) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+
+    //update pool rewards
+    {
+        let pool = &mut ctx.accounts.stake_pool;
+        update_pool_rewards(pool, current_time)?;
+    }
+    let user_stake = &mut ctx.accounts.user_stake_account;
+    accrue_user_rewards(user_stake, &mut ctx.accounts.stake_pool)?;
+
+    let amount = user_stake.pending_rewards;
+    require!(amount > 0, StakingError::NoRewardsToClaim);
+
+    let stake_mint_key = ctx.accounts.stake_pool.stake_mint.key();
+    let reward_mint_key = ctx.accounts.stake_pool.reward_mint.key();
+    let community_creator = ctx.accounts.stake_pool.community_creator;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"stake_pool",
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
+        creator_seed(&community_creator),
+        &[ctx.accounts.stake_pool.bump],
+    ]];
+    let vault_balance = ctx.accounts.pool_stake_vault.amount;
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.pool_reward_vault.to_account_info(),
+                mint: ctx.accounts.stake_mint.to_account_info(),
+                to: ctx.accounts.pool_stake_vault.to_account_info(),
+                authority: ctx.accounts.stake_pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        ctx.accounts.stake_mint.decimals,
+    )?;
+    // the full reward leaves the reward vault, only what arrives is staked
+    let staked = received_amount(&mut ctx.accounts.pool_stake_vault, vault_balance)?;
+
+    //update state
+    let user_stake = &mut ctx.accounts.user_stake_account;
+    user_stake.pending_rewards = 0;
+    user_stake.amount_staked = user_stake
+        .amount_staked
+        .checked_add(staked)
+        .ok_or(StakingError::MathOverflow)?;
+    add_stake_lot(user_stake, staked, current_time, 0)?;
+    let pool = &mut ctx.accounts.stake_pool;
+    release_owed_rewards(pool, amount);
+    pool.total_staked = pool
+        .total_staked
+        .checked_add(staked)
+        .ok_or(StakingError::MathOverflow)?;
+    update_effective_stake(pool, user_stake)?;
+    let by_owner = ctx.accounts.caller.key() == user_stake.user;
+    record_stake_checkpoints(pool, user_stake, by_owner)?;
+
+    emit!(RewardsCompounded {
+        pool: pool.key(),
+        user: user_stake.user,
+        amount: staked,
+        user_total_staked: user_stake.amount_staked,
+        pool_total_staked: pool.total_staked,
+        reward_per_token_stored: pool.reward_per_token_stored,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Compound<'info> {
+    pub caller: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = !stake_pool.nft_pool @ StakingError::NftPoolUnsupported,
+        constraint = stake_pool.stake_mint == stake_pool.reward_mint @ StakingError::CompoundUnsupported,
+        constraint = stake_pool.reward_vesting_duration == 0 @ StakingError::CompoundWhileVesting,
+        constraint = !stake_pool.paused @ StakingError::PoolPaused,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user_stake_account.user.as_ref()],
+        bump = user_stake_account.bump,
+        constraint = user_stake_account.user == caller.key()
+            || user_stake_account.auto_compound @ StakingError::AutoCompoundNotEnabled,
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+    #[account(
+        mut,
+        seeds = [b"stake_vault", stake_pool.key().as_ref(), stake_pool.stake_mint.as_ref()],
+        bump
+    )]
+    pub pool_stake_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"reward_vault", stake_pool.key().as_ref(), stake_pool.reward_mint.as_ref()],
+        bump
+    )]
+    pub pool_reward_vault: InterfaceAccount<'info, TokenAccount>,
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
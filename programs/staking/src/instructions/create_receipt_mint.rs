@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+use crate::{error::StakingError, state::StakePool};
+
+pub fn process_create_receipt_mint(ctx: Context<CreateReceiptMint>) -> Result<()> {
+    let pool = &mut ctx.accounts.stake_pool;
+    pool.receipt_mint = Some(ctx.accounts.receipt_mint.key());
+
+    msg!("Receipt mint created: {}", ctx.accounts.receipt_mint.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateReceiptMint<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == stake_pool.authority @ StakingError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    // the receipt stake restakes its rewards and cannot hold back a redemption
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = !stake_pool.nft_pool @ StakingError::NftPoolUnsupported,
+        constraint = stake_pool.receipt_mint.is_none() @ StakingError::ReceiptMintUnavailable,
+        constraint = (stake_pool.is_points_only() || stake_pool.stake_mint == stake_pool.reward_mint)
+            && stake_pool.min_stake_duration == 0
+            && stake_pool.reward_vesting_duration == 0 @ StakingError::ReceiptPoolTermsUnsupported,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(address = stake_pool.stake_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = stake_mint.decimals,
+        mint::authority = stake_pool,
+        mint::token_program = token_program,
+        seeds = [b"receipt_mint", stake_pool.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
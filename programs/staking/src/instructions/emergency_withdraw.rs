@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::StakingError;
+use crate::events::EmergencyWithdrawn;
+use crate::state::{creator_seed, StakePool, UserStakeAccount};
+use crate::utility::{
+    accrue_user_rewards, record_stake_checkpoints, release_owed_rewards,
+    update_effective_stake, update_pool_rewards,
+};
+
+/// Returns the user's principal and forfeits every unclaimed reward. Lots
+/// hard-locked by a `stake_for` funder stay staked. The others must have
+/// served the pool's minimum duration, unless the pool is paused.
+pub fn process_emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let user_stake = &mut ctx.accounts.user_stake_account;
+    let mut amount: u64 = 0;
+    for lot in user_stake
+        .lots
+        .iter()
+        .filter(|lot| lot.unlock_time <= current_time)
+    {
+        if !ctx.accounts.stake_pool.paused {
+            require!(
+                current_time - lot.start_time >= ctx.accounts.stake_pool.min_stake_duration,
+                StakingError::MinimumStakeDurationNotMet
+            );
+        }
+        amount = amount
+            .checked_add(lot.amount)
+            .ok_or(StakingError::MathOverflow)?;
+    }
+    require!(amount > 0, StakingError::NothingStaked);
+
+    // keep the accumulator right for everyone else before the total changes
+    {
+        let pool = &mut ctx.accounts.stake_pool;
+        update_pool_rewards(pool, current_time)?;
+    }
+    accrue_user_rewards(user_stake, &mut ctx.accounts.stake_pool)?;
+
+    let stake_mint_key = ctx.accounts.stake_pool.stake_mint.key();
+    let reward_mint_key = ctx.accounts.stake_pool.reward_mint.key();
+    let community_creator = ctx.accounts.stake_pool.community_creator;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"stake_pool",
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
+        creator_seed(&community_creator),
+        &[ctx.accounts.stake_pool.bump],
+    ]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.pool_stake_vault.to_account_info(),
+                mint: ctx.accounts.stake_mint.to_account_info(),
+                to: ctx.accounts.user_token_account.to_account_info(),
+                authority: ctx.accounts.stake_pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        ctx.accounts.stake_mint.decimals,
+    )?;
+
+    //update state
+    let forfeited = user_stake.pending_rewards;
+    user_stake.amount_staked = user_stake
+        .amount_staked
+        .checked_sub(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    user_stake.lots.retain(|lot| lot.unlock_time > current_time);
+    user_stake.pending_rewards = 0;
+
+    let pool = &mut ctx.accounts.stake_pool;
+    release_owed_rewards(pool, forfeited);
+    pool.total_staked = pool
+        .total_staked
+        .checked_sub(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    update_effective_stake(pool, user_stake)?;
+    record_stake_checkpoints(pool, user_stake, true)?;
+
+    emit!(EmergencyWithdrawn {
+        pool: pool.key(),
+        user: user_stake.user,
+        amount,
+        forfeited_rewards: forfeited,
+        pool_total_staked: pool.total_staked,
+        reward_per_token_stored: pool.reward_per_token_stored,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = !stake_pool.nft_pool @ StakingError::NftPoolUnsupported
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user.key().as_ref()],
+        bump = user_stake_account.bump
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == stake_pool.stake_mint,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"stake_vault", stake_pool.key().as_ref(), stake_pool.stake_mint.as_ref()],
+        bump
+    )]
+    pub pool_stake_vault: InterfaceAccount<'info, TokenAccount>,
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
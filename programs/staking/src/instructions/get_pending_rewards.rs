@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::error::StakingError;
+use crate::state::{StakePool, UserStakeAccount};
+use crate::utility::{calculate_pending_rewards, update_pool_rewards};
+
+/// Read-only: returns the rewards `claim_rewards` would pay right now.
+/// Nothing is written; clients read the value from simulation return data.
+pub fn process_get_pending_rewards(ctx: Context<GetPendingRewards>) -> Result<u64> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let mut pool = ctx.accounts.stake_pool.clone().into_inner();
+    update_pool_rewards(&mut pool, current_time)?;
+
+    let user_stake = &ctx.accounts.user_stake_account;
+    let pending = calculate_pending_rewards(user_stake, &pool)?
+        .checked_add(user_stake.pending_rewards)
+        .ok_or(StakingError::MathOverflow)?;
+    Ok(pending)
+}
+
+#[derive(Accounts)]
+pub struct GetPendingRewards<'info> {
+    #[account(
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user_stake_account.user.as_ref()],
+        bump = user_stake_account.bump
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+}
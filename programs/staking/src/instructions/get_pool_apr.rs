@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{BPS_DENOMINATOR, PRECISION, SECONDS_PER_YEAR};
+use crate::state::StakePool;
+use crate::utility::update_pool_rewards;
+
+/// Read-only: returns the current annual emissions per unboosted staked token
+/// in bps, counting reward and stake tokens one to one. Zero for an empty pool.
+pub fn process_get_pool_apr(ctx: Context<GetPoolApr>) -> Result<u64> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let mut pool = ctx.accounts.stake_pool.clone().into_inner();
+    update_pool_rewards(&mut pool, current_time)?;
+
+    if pool.total_effective_stake == 0 {
+        return Ok(0);
+    }
+    let apr_bps = pool.reward_rate as u128 * SECONDS_PER_YEAR as u128 * BPS_DENOMINATOR as u128
+        / PRECISION as u128
+        / pool.total_effective_stake as u128;
+    Ok(u64::try_from(apr_bps).unwrap_or(u64::MAX))
+}
+
+#[derive(Accounts)]
+pub struct GetPoolApr<'info> {
+    #[account(
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{StakePool, UserStakeAccount};
+
+/// Read-only: returns the user's staked balance at the end of `slot`, e.g. for
+/// a vote snapshotted when its proposal was created. Only the latest
+/// `MAX_STAKE_CHECKPOINTS` balance changes are held; older slots fail. Once
+/// they are all taken, deposits made for the user by someone else only show
+/// from the user's next change.
+pub fn process_get_stake_at(ctx: Context<GetStakeAt>, slot: u64) -> Result<u64> {
+    ctx.accounts.user_stake_account.stake_history.balance_at(slot)
+}
+
+#[derive(Accounts)]
+pub struct GetStakeAt<'info> {
+    #[account(
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user_stake_account.user.as_ref()],
+        bump = user_stake_account.bump
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{StakePool, UserStakeAccount};
+use crate::utility::{calculate_stake_points, update_pool_rewards};
+
+/// Read-only: returns the user's stake points (stake-seconds) up to now.
+/// Nothing is written; clients read the value from simulation return data.
+pub fn process_get_stake_points(ctx: Context<GetStakePoints>) -> Result<u128> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let mut pool = ctx.accounts.stake_pool.clone().into_inner();
+    update_pool_rewards(&mut pool, current_time)?;
+
+    calculate_stake_points(&ctx.accounts.user_stake_account, &pool)
+}
+
+#[derive(Accounts)]
+pub struct GetStakePoints<'info> {
+    #[account(
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user_stake_account.user.as_ref()],
+        bump = user_stake_account.bump
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+}
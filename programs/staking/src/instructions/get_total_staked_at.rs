@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::state::StakePool;
+
+/// Read-only: returns the pool's `total_staked` at the end of `slot`. Only the
+/// latest `MAX_STAKE_CHECKPOINTS` changes are held; older slots fail.
+pub fn process_get_total_staked_at(ctx: Context<GetTotalStakedAt>, slot: u64) -> Result<u64> {
+    ctx.accounts.stake_pool.stake_history.balance_at(slot)
+}
+
+#[derive(Accounts)]
+pub struct GetTotalStakedAt<'info> {
+    #[account(
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
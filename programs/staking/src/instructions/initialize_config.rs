@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_POOL_CREATORS;
+use crate::error::StakingError;
+use crate::program::Staking;
+use crate::state::{Config, PoolRegistry};
+
+/// Creates the program-wide `Config` and the empty `PoolRegistry`. Only the
+/// program's upgrade authority can call it, and only once; `initialize_pool`
+/// and the other pool constructors require both accounts.
+pub fn process_initialize_config(
+    ctx: Context<InitializeConfig>,
+    pool_creators: Vec<Pubkey>,
+    creation_fee: Option<u64>,
+    fee_recipient: Pubkey,
+) -> Result<()> {
+    require!(pool_creators.len() <= MAX_POOL_CREATORS, StakingError::TooManyPoolCreators);
+
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.pool_creators = pool_creators;
+    config.creation_fee = creation_fee;
+    config.fee_recipient = fee_recipient;
+    config.bump = ctx.bumps.config;
+
+    let registry = &mut ctx.accounts.pool_registry;
+    registry.bump = ctx.bumps.pool_registry;
+    registry.pool_count = 0;
+
+    msg!("Initialized config, admin: {}", config.admin);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + PoolRegistry::INIT_SPACE,
+        seeds = [b"pool_registry"],
+        bump
+    )]
+    pub pool_registry: Account<'info, PoolRegistry>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Staking>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ StakingError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
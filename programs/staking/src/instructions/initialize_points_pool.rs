@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::STAKE_POOL_VERSION;
+use crate::error::StakingError;
+use crate::events::PoolInitialized;
+use crate::state::{
+    creator_seed, Config, EarlyExitConfig, PoolEntry, PoolLimits, PoolRegistry, StakePool,
+};
+use crate::utility::{add_registry_entry, charge_creation_fee, check_mint_extensions};
+
+/// Creates a pool without a reward token. Stakers only accrue stake points
+/// (stake-seconds), read through `get_stake_points`, e.g. for loyalty or
+/// airdrop campaigns. The pool's `reward_mint` is the default pubkey.
+pub fn process_initialize_points_pool(
+    ctx: Context<InitializePointsPool>,
+    minimum_stake_duration: i64,
+    early_exit: EarlyExitConfig,
+    limits: PoolLimits,
+) -> Result<()> {
+    // with no reward mint, penalties can only go to a treasury
+    early_exit.validate(&ctx.accounts.stake_mint.key(), &Pubkey::default())?;
+    limits.validate()?;
+    check_mint_extensions(&ctx.accounts.stake_mint)?;
+
+    let pool = &mut ctx.accounts.stake_pool;
+
+    pool.authority = ctx.accounts.authority.key();
+    pool.community_creator = ctx.accounts.config.community_creator(&pool.authority);
+    pool.stake_mint = ctx.accounts.stake_mint.key();
+    pool.reward_mint = Pubkey::default();
+    pool.total_staked = 0;
+    pool.total_effective_stake = 0;
+    pool.boost = None;
+    pool.referral_bps = 0;
+    pool.referral_budget = 0;
+    pool.nft_pool = false;
+    pool.default_nft_weight = 0;
+    pool.reward_rate = 0;
+    pool.min_stake_duration = minimum_stake_duration;
+    pool.early_exit = early_exit;
+    pool.limits = limits;
+    pool.last_updated = Clock::get()?.unix_timestamp;
+    pool.bump = ctx.bumps.stake_pool;
+    pool.version = STAKE_POOL_VERSION;
+    // the pool holds nothing from its first slot on
+    pool.stake_history.record(Clock::get()?.slot, 0);
+
+    let official = charge_creation_fee(
+        &ctx.accounts.config,
+        &ctx.accounts.authority,
+        &ctx.accounts.fee_recipient,
+        &ctx.accounts.system_program,
+    )?;
+    add_registry_entry(
+        &mut ctx.accounts.pool_registry,
+        &mut ctx.accounts.pool_entry,
+        &mut ctx.accounts.stake_pool,
+        ctx.accounts.authority.key(),
+        official,
+        ctx.bumps.pool_entry,
+    )?;
+
+    let pool = &ctx.accounts.stake_pool;
+    emit!(PoolInitialized {
+        pool: pool.key(),
+        authority: pool.authority,
+        stake_mint: pool.stake_mint,
+        reward_mint: pool.reward_mint,
+        reward_rate: 0,
+        min_stake_duration: minimum_stake_duration,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializePointsPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Account<'info, PoolRegistry>,
+    /// CHECK: receives the creation fee, if one is charged.
+    #[account(mut, address = config.fee_recipient @ StakingError::InvalidFeeRecipient)]
+    pub fee_recipient: UncheckedAccount<'info>,
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + StakePool::INIT_SPACE,
+        seeds = [
+            b"stake_pool",
+            stake_mint.key().as_ref(),
+            Pubkey::default().as_ref(),
+            creator_seed(&config.community_creator(authority.key))
+        ],
+        bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PoolEntry::INIT_SPACE,
+        seeds = [b"pool_entry", pool_registry.pool_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pool_entry: Account<'info, PoolEntry>,
+    #[account(
+        init,
+        payer = authority,
+        token::mint = stake_mint,
+        token::authority = stake_pool,
+        token::token_program = token_program,
+        seeds = [b"stake_vault", stake_pool.key().as_ref(), stake_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_stake_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
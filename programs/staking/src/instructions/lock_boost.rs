@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::StakingError;
+use crate::events::BoostLocked;
+use crate::state::{StakePool, UserStakeAccount};
+use crate::utility::{apply_boost, received_amount, token_boost};
+
+/// Locks the user's boost tokens in the pool's boost vault. Once
+/// `min_balance` is locked the stake is boosted, until `unlock_boost` takes
+/// the tokens back out. Held by the pool, the tokens cannot boost another
+/// staker in the meantime.
+pub fn process_lock_boost(ctx: Context<LockBoost>, amount: u64) -> Result<()> {
+    require!(amount > 0, StakingError::InvalidAmount);
+    let current_time = Clock::get()?.unix_timestamp;
+    let vault_balance = ctx.accounts.boost_vault.amount;
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_boost_account.to_account_info(),
+                mint: ctx.accounts.boost_mint.to_account_info(),
+                to: ctx.accounts.boost_vault.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.boost_mint.decimals,
+    )?;
+    // credit what the vault received, net of any transfer fee
+    let amount = received_amount(&mut ctx.accounts.boost_vault, vault_balance)?;
+
+    let user_stake = &mut ctx.accounts.user_stake_account;
+    user_stake.boost_locked = user_stake
+        .boost_locked
+        .checked_add(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    let pool = &mut ctx.accounts.stake_pool;
+    pool.boost_locked = pool
+        .boost_locked
+        .checked_add(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    let boost_bps = token_boost(pool.boost.as_ref(), user_stake.boost_locked);
+    apply_boost(pool, user_stake, boost_bps, current_time)?;
+
+    emit!(BoostLocked {
+        pool: pool.key(),
+        user: user_stake.user,
+        amount,
+        user_boost_locked: user_stake.boost_locked,
+        boost_bps,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct LockBoost<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = !stake_pool.paused @ StakingError::PoolPaused,
+        constraint = stake_pool.boost_lock_mint() == Some(boost_mint.key()) @ StakingError::BoostNotLockable,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user.key().as_ref()],
+        bump = user_stake_account.bump
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+    #[account(
+        mut,
+        constraint = user_boost_account.owner == user.key(),
+        constraint = user_boost_account.mint == boost_mint.key(),
+    )]
+    pub user_boost_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        token::mint = boost_mint,
+        token::authority = stake_pool,
+        token::token_program = token_program,
+        seeds = [b"boost_vault", stake_pool.key().as_ref(), boost_mint.key().as_ref()],
+        bump
+    )]
+    pub boost_vault: InterfaceAccount<'info, TokenAccount>,
+    pub boost_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
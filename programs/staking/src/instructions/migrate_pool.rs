@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::constants::{UNVERSIONED_STAKE_POOL_LEN, STAKE_POOL_VERSION};
+use crate::error::StakingError;
+use crate::events::AccountMigrated;
+use crate::state::{StakeHistory, StakePool};
+use crate::utility::grow_account;
+
+/// `StakePool` as first deployed, before versioning.
+#[derive(AnchorDeserialize)]
+struct UnversionedStakePool {
+    authority: Pubkey,
+    stake_mint: Pubkey,
+    reward_mint: Pubkey,
+    reward_rate: u64,
+    reward_per_token_stored: u64,
+    last_updated: i64,
+    min_stake_duration: i64,
+    total_staked: u64,
+    bump: u8,
+}
+
+impl UnversionedStakePool {
+    fn decode(data: &[u8]) -> Result<Self> {
+        require!(
+            data.starts_with(StakePool::DISCRIMINATOR),
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        Ok(Self::deserialize(&mut &data[8..])?)
+    }
+
+    /// The pool under the current layout. Unpaid rewards were never
+    /// tracked, so the whole reward vault is taken as owed to the existing
+    /// stakers. Nobody is boosted yet, and the balance history and stake
+    /// points start at migration.
+    fn upgrade(
+        self,
+        reward_vault: Option<&InterfaceAccount<TokenAccount>>,
+        pool: &Pubkey,
+        slot: u64,
+    ) -> Result<StakePool> {
+        let (vault_key, _) = Pubkey::find_program_address(
+            &[b"reward_vault", pool.as_ref(), self.reward_mint.as_ref()],
+            &crate::ID,
+        );
+        let reward_vault = reward_vault
+            .filter(|vault| vault.key() == vault_key)
+            .ok_or(StakingError::InvalidRewardVault)?;
+        let mut stake_history = StakeHistory::default();
+        stake_history.record(slot, self.total_staked);
+        Ok(StakePool {
+            authority: self.authority,
+            stake_mint: self.stake_mint,
+            reward_mint: self.reward_mint,
+            reward_rate: self.reward_rate,
+            reward_per_token_stored: self.reward_per_token_stored,
+            last_updated: self.last_updated,
+            min_stake_duration: self.min_stake_duration,
+            total_staked: self.total_staked,
+            total_effective_stake: self.total_staked,
+            rewards_owed: reward_vault.amount,
+            stake_history,
+            bump: self.bump,
+            version: STAKE_POOL_VERSION,
+            ..Default::default()
+        })
+    }
+}
+
+/// Upgrades a pool from the original layout in place, growing it and filling
+/// in every field added since. Anyone can migrate; the payer covers the extra
+/// rent.
+pub fn process_migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+    let info = ctx.accounts.stake_pool.to_account_info();
+    // only the original layout predates versioning, and it has no version byte to read
+    require!(
+        info.data_len() == UNVERSIONED_STAKE_POOL_LEN,
+        StakingError::AccountUpToDate
+    );
+    let pool = UnversionedStakePool::decode(&info.try_borrow_data()?)?.upgrade(
+        ctx.accounts.pool_reward_vault.as_ref(),
+        info.key,
+        Clock::get()?.slot,
+    )?;
+    grow_account(
+        &info,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        8 + StakePool::INIT_SPACE,
+    )?;
+    pool.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    emit!(AccountMigrated {
+        account: info.key(),
+        from_version: 0,
+        to_version: STAKE_POOL_VERSION,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: may still have an old layout, so it is loaded by hand. The owner
+    /// is checked here and the discriminator on deserialization.
+    #[account(mut, owner = crate::ID)]
+    pub stake_pool: UncheckedAccount<'info>,
+    /// Required for pools from before versioning, checked by address.
+    pub pool_reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{
+    BPS_DENOMINATOR, STAKE_POOL_VERSION, UNVERSIONED_USER_STAKE_LEN, USER_STAKE_VERSION,
+};
+use crate::error::StakingError;
+use crate::events::AccountMigrated;
+use crate::state::{StakeHistory, StakeLot, StakePool, UserStakeAccount};
+use crate::utility::grow_account;
+
+/// `UserStakeAccount` as first deployed, before versioning.
+#[derive(AnchorDeserialize)]
+struct UnversionedUserStake {
+    user: Pubkey,
+    stake_pool: Pubkey,
+    amount_staked: u64,
+    reward_per_token_paid: u64,
+    pending_rewards: u64,
+    stake_start_time: i64,
+    bump: u8,
+}
+
+impl UnversionedUserStake {
+    fn decode(data: &[u8]) -> Result<Self> {
+        require!(
+            data.starts_with(UserStakeAccount::DISCRIMINATOR),
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        Ok(Self::deserialize(&mut &data[8..])?)
+    }
+
+    /// The account under the current layout. The whole stake becomes one
+    /// lot, locked from `stake_start_time` as before. Nobody is boosted yet,
+    /// and the balance history and stake points start at migration.
+    fn upgrade(self, pool: &StakePool, slot: u64) -> UserStakeAccount {
+        let lots = if self.amount_staked > 0 {
+            vec![StakeLot {
+                amount: self.amount_staked,
+                start_time: self.stake_start_time,
+                unlock_time: 0,
+            }]
+        } else {
+            Vec::new()
+        };
+        let mut stake_history = StakeHistory::default();
+        stake_history.record(slot, self.amount_staked);
+        UserStakeAccount {
+            user: self.user,
+            stake_pool: self.stake_pool,
+            amount_staked: self.amount_staked,
+            reward_per_token_paid: self.reward_per_token_paid,
+            pending_rewards: self.pending_rewards,
+            lots,
+            points_per_token_paid: pool.points_per_token_stored,
+            stake_history,
+            // matches the pool's total_effective_stake, seeded from total_staked
+            boost_bps: BPS_DENOMINATOR as u16,
+            effective_stake: self.amount_staked,
+            bump: self.bump,
+            version: USER_STAKE_VERSION,
+            ..Default::default()
+        }
+    }
+}
+
+/// Upgrades a user stake account from the original layout in place, growing
+/// it and filling in every field added since. The pool has to be migrated
+/// first. Anyone can migrate; the payer covers the extra rent.
+pub fn process_migrate_user_stake(ctx: Context<MigrateUserStake>) -> Result<()> {
+    let info = ctx.accounts.user_stake_account.to_account_info();
+    // only the original layout predates versioning, and it has no version byte to read
+    require!(
+        info.data_len() == UNVERSIONED_USER_STAKE_LEN,
+        StakingError::AccountUpToDate
+    );
+    let old = UnversionedUserStake::decode(&info.try_borrow_data()?)?;
+    let pool = &ctx.accounts.stake_pool;
+    require!(old.stake_pool == pool.key(), StakingError::StakePoolMismatch);
+    require!(pool.version == STAKE_POOL_VERSION, StakingError::AccountNotMigrated);
+    let user_stake = old.upgrade(pool, Clock::get()?.slot);
+    grow_account(
+        &info,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        8 + UserStakeAccount::INIT_SPACE,
+    )?;
+    user_stake.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    emit!(AccountMigrated {
+        account: info.key(),
+        from_version: 0,
+        to_version: USER_STAKE_VERSION,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateUserStake<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: may still have an old layout, so it is loaded by hand. The owner
+    /// is checked here and the discriminator on deserialization.
+    #[account(mut, owner = crate::ID)]
+    pub user_stake_account: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    pub system_program: Program<'info, System>,
+}
@@ -7,4 +7,80 @@ pub use unstake::*;
 pub mod claim_reward;
 pub use claim_reward::*;
 pub mod update_pool;
-pub use update_pool::*;
\ No newline at end of file
+pub use update_pool::*;
+pub mod compound;
+pub use compound::*;
+pub mod set_auto_compound;
+pub use set_auto_compound::*;
+pub mod create_receipt_mint;
+pub use create_receipt_mint::*;
+pub mod stake_liquid;
+pub use stake_liquid::*;
+pub mod unstake_liquid;
+pub use unstake_liquid::*;
+pub mod set_paused;
+pub use set_paused::*;
+pub mod emergency_withdraw;
+pub use emergency_withdraw::*;
+pub mod propose_authority;
+pub use propose_authority::*;
+pub mod accept_authority;
+pub use accept_authority::*;
+pub mod set_reward_schedule;
+pub use set_reward_schedule::*;
+pub mod close_stake_account;
+pub use close_stake_account::*;
+pub mod set_slashing_config;
+pub use set_slashing_config::*;
+pub mod slash;
+pub use slash::*;
+pub mod withdraw_excess_rewards;
+pub use withdraw_excess_rewards::*;
+pub mod recover_token;
+pub use recover_token::*;
+pub mod get_pending_rewards;
+pub use get_pending_rewards::*;
+pub mod get_pool_apr;
+pub use get_pool_apr::*;
+pub mod withdraw_vested;
+pub use withdraw_vested::*;
+pub mod stake_for;
+pub use stake_for::*;
+pub mod migrate_pool;
+pub use migrate_pool::*;
+pub mod migrate_user_stake;
+pub use migrate_user_stake::*;
+pub mod initialize_points_pool;
+pub use initialize_points_pool::*;
+pub mod get_stake_points;
+pub use get_stake_points::*;
+pub mod get_stake_at;
+pub use get_stake_at::*;
+pub mod get_total_staked_at;
+pub use get_total_staked_at::*;
+pub mod set_boost;
+pub use set_boost::*;
+pub mod refresh_boost;
+pub use refresh_boost::*;
+pub mod lock_boost;
+pub use lock_boost::*;
+pub mod unlock_boost;
+pub use unlock_boost::*;
+pub mod initialize_nft_pool;
+pub use initialize_nft_pool::*;
+pub mod set_nft_weight;
+pub use set_nft_weight::*;
+pub mod stake_nft;
+pub use stake_nft::*;
+pub mod unstake_nft;
+pub use unstake_nft::*;
+pub mod initialize_config;
+pub use initialize_config::*;
+pub mod update_config;
+pub use update_config::*;
+pub mod register_pool;
+pub use register_pool::*;
+pub mod set_referral_bps;
+pub use set_referral_bps::*;
+pub mod claim_referral_rewards;
+pub use claim_referral_rewards::*;
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::StakingError, state::StakePool};
+
+/// First half of an authority handover; the new key must call `accept_authority`.
+/// Proposing again replaces the pending authority.
+pub fn process_propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    ctx.accounts.stake_pool.pending_authority = Some(new_authority);
+
+    msg!("Proposed new pool authority: {}", new_authority);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        constraint = authority.key() == stake_pool.authority @ StakingError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
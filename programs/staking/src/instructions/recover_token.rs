@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::StakingError;
+use crate::state::StakePool;
+
+/// Returns tokens sent to a pool-owned account by mistake. The stake vault
+/// only gives up its balance above `total_staked`, and the boost vault its
+/// balance above `boost_locked`; reward vault funds go
+/// through `withdraw_excess_rewards` instead, and staked NFTs are never
+/// recoverable.
+pub fn process_recover_token(ctx: Context<RecoverToken>, amount: u64) -> Result<()> {
+    require!(amount > 0, StakingError::InvalidAmount);
+    let pool = &ctx.accounts.stake_pool;
+    let pool_key = pool.key();
+    let source = &ctx.accounts.source;
+
+    let (stake_vault, _) = Pubkey::find_program_address(
+        &[b"stake_vault", pool_key.as_ref(), pool.stake_mint.as_ref()],
+        ctx.program_id,
+    );
+    let (reward_vault, _) = Pubkey::find_program_address(
+        &[b"reward_vault", pool_key.as_ref(), pool.reward_mint.as_ref()],
+        ctx.program_id,
+    );
+    require_keys_neq!(source.key(), reward_vault, StakingError::TokenNotRecoverable);
+    if pool.nft_pool {
+        let (nft_vault, _) = Pubkey::find_program_address(
+            &[b"nft_vault", pool_key.as_ref(), source.mint.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_neq!(source.key(), nft_vault, StakingError::TokenNotRecoverable);
+    }
+    let (boost_vault, _) = Pubkey::find_program_address(
+        &[b"boost_vault", pool_key.as_ref(), source.mint.as_ref()],
+        ctx.program_id,
+    );
+    let recoverable = if source.key() == stake_vault {
+        source.amount.saturating_sub(pool.total_staked)
+    } else if source.key() == boost_vault && pool.boost_lock_mint() == Some(source.mint) {
+        source.amount.saturating_sub(pool.boost_locked)
+    } else {
+        source.amount
+    };
+    require!(amount <= recoverable, StakingError::TokenNotRecoverable);
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"stake_pool",
+        pool.stake_mint.as_ref(),
+        pool.reward_mint.as_ref(),
+        pool.creator_seed(),
+        &[pool.bump],
+    ]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: source.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.stake_pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    msg!("Recovered {} tokens of mint {}", amount, ctx.accounts.mint.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RecoverToken<'info> {
+    #[account(
+        constraint = authority.key() == stake_pool.authority @ StakingError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        constraint = source.owner == stake_pool.key() @ StakingError::TokenNotRecoverable,
+    )]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = destination.mint == source.mint,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = source.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
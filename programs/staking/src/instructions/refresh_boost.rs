@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::StakingError;
+use crate::events::BoostRefreshed;
+use crate::state::{BoostClaim, BoostConfig, BoostSource, StakePool, UserStakeAccount};
+use crate::utility::{apply_boost, collection_boost, token_boost};
+
+/// Recomputes a staker's boost after the pool's boost changed, or for
+/// collection boosts from the NFT they chose. Token boosts come from tokens
+/// locked through `lock_boost`, which cannot move on to another staker.
+/// Anyone can call it, so a collection boost lapses as soon as the NFT is
+/// moved or sold; only the staker can choose a different
+/// `boost_token_account`, or none.
+///
+/// A collection boost also needs the NFT's `BoostClaim`, created here as
+/// `boost_claim` and released as `previous_boost_claim` when the boost
+/// lapses, so an NFT passed along boosts one staker at a time. Whoever
+/// takes it over refreshes its previous staker first.
+pub fn process_refresh_boost(ctx: Context<RefreshBoost>) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let user_stake = &mut ctx.accounts.user_stake_account;
+    let boost_token_account = ctx.accounts.boost_token_account.as_ref();
+
+    if ctx.accounts.caller.key() == user_stake.user {
+        user_stake.boost_account = boost_token_account.map(|account| account.key());
+    } else {
+        require!(
+            user_stake.boost_account == boost_token_account.map(|account| account.key()),
+            StakingError::InvalidBoostAccount
+        );
+    }
+
+    let boost = ctx.accounts.stake_pool.boost;
+    let boost_mint = ctx.accounts.boost_mint.as_ref();
+    let (boost_bps, holding) = match (&boost, boost_token_account, boost_mint) {
+        (
+            Some(boost @ BoostConfig { source: BoostSource::Collection { .. }, .. }),
+            Some(account),
+            Some(mint),
+        ) => (
+            collection_boost(
+                boost,
+                &user_stake.user,
+                account,
+                mint,
+                ctx.accounts.boost_nft_metadata.as_ref().map(|metadata| metadata.as_ref()),
+            )?,
+            Some(mint.key()),
+        ),
+        _ => (token_boost(boost.as_ref(), user_stake.boost_locked), None),
+    };
+    let holding = holding.filter(|_| boost_bps != BPS_DENOMINATOR as u16);
+
+    let claim = holding.map(|holding| {
+        Pubkey::find_program_address(
+            &[b"boost_claim", ctx.accounts.stake_pool.key().as_ref(), holding.as_ref()],
+            &crate::ID,
+        )
+        .0
+    });
+    // a claim created for nothing would lock the holding
+    let needed = claim.filter(|claim| user_stake.boost_claim != Some(*claim));
+    require!(
+        ctx.accounts.boost_claim.as_ref().map(|boost_claim| boost_claim.key()) == needed,
+        StakingError::InvalidBoostClaim
+    );
+    if claim != user_stake.boost_claim {
+        if user_stake.boost_claim.is_some() {
+            let previous = ctx
+                .accounts
+                .previous_boost_claim
+                .as_ref()
+                .ok_or(StakingError::InvalidBoostClaim)?;
+            previous.close(ctx.accounts.user.to_account_info())?;
+        }
+        if let Some(boost_claim) = ctx.accounts.boost_claim.as_mut() {
+            boost_claim.user_stake = user_stake.key();
+            boost_claim.bump = ctx.bumps.boost_claim.ok_or(StakingError::InvalidBoostClaim)?;
+        }
+        user_stake.boost_claim = claim;
+    }
+
+    let pool = &mut ctx.accounts.stake_pool;
+    apply_boost(pool, user_stake, boost_bps, current_time)?;
+
+    emit!(BoostRefreshed {
+        pool: pool.key(),
+        user: user_stake.user,
+        boost_bps,
+        effective_stake: user_stake.effective_stake,
+        pool_total_effective_stake: pool.total_effective_stake,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefreshBoost<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user_stake_account.user.as_ref()],
+        bump = user_stake_account.bump
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+    /// CHECK: receives the rent of a released claim
+    #[account(mut, address = user_stake_account.user)]
+    pub user: UncheckedAccount<'info>,
+    /// CHECK: the staker's NFT account. Parsed in `collection_boost`, where an
+    /// account that is closed, emptied or not a token account earns no boost.
+    pub boost_token_account: Option<UncheckedAccount<'info>>,
+    /// CHECK: the held NFT's mint. Parsed in `collection_boost`.
+    pub boost_mint: Option<UncheckedAccount<'info>>,
+    /// CHECK: the held NFT's Metaplex metadata, for NFTs outside Token-2022
+    /// groups. Parsed in `collection_boost`.
+    pub boost_nft_metadata: Option<UncheckedAccount<'info>>,
+    /// CHECK: the NFT mint that `boost_claim` is keyed by. Matched against the
+    /// boost in the handler.
+    pub claimed_holding: Option<UncheckedAccount<'info>>,
+    /// Claim for a new boost, paid by the caller. Fails if another staker
+    /// holds it.
+    #[account(
+        init,
+        payer = caller,
+        space = 8 + BoostClaim::INIT_SPACE,
+        seeds = [
+            b"boost_claim",
+            stake_pool.key().as_ref(),
+            claimed_holding.as_ref().map(|holding| holding.key()).unwrap_or_default().as_ref()
+        ],
+        bump
+    )]
+    pub boost_claim: Option<Account<'info, BoostClaim>>,
+    /// The claim behind the boost being replaced, closed to the staker.
+    #[account(
+        mut,
+        constraint = Some(previous_boost_claim.key()) == user_stake_account.boost_claim
+            @ StakingError::InvalidBoostClaim
+    )]
+    pub previous_boost_claim: Option<Account<'info, BoostClaim>>,
+    pub system_program: Program<'info, System>,
+}
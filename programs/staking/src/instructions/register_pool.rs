@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::error::StakingError;
+use crate::state::{Config, PoolEntry, PoolRegistry, StakePool};
+use crate::utility::add_registry_entry;
+
+/// Lists a pool opened before the registry existed. Admin only; the pool is
+/// listed as official with its authority as creator.
+pub fn process_register_pool(ctx: Context<RegisterPool>) -> Result<()> {
+    let creator = ctx.accounts.stake_pool.authority;
+    add_registry_entry(
+        &mut ctx.accounts.pool_registry,
+        &mut ctx.accounts.pool_entry,
+        &mut ctx.accounts.stake_pool,
+        creator,
+        true,
+        ctx.bumps.pool_entry,
+    )
+}
+
+#[derive(Accounts)]
+pub struct RegisterPool<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ StakingError::Unauthorized
+    )]
+    pub admin: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Account<'info, PoolRegistry>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = !stake_pool.registered @ StakingError::PoolAlreadyRegistered
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + PoolEntry::INIT_SPACE,
+        seeds = [b"pool_entry", pool_registry.pool_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pool_entry: Account<'info, PoolEntry>,
+    pub system_program: Program<'info, System>,
+}
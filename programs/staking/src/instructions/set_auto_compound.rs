@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{StakePool, UserStakeAccount};
+
+pub fn process_set_auto_compound(ctx: Context<SetAutoCompound>, enabled: bool) -> Result<()> {
+    ctx.accounts.user_stake_account.auto_compound = enabled;
+
+    msg!("Auto-compounding enabled: {}", enabled);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetAutoCompound<'info> {
+    pub user: Signer<'info>,
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user.key().as_ref()],
+        bump = user_stake_account.bump
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::StakingError,
+    state::{BoostConfig, BoostSource, StakePool},
+};
+
+/// Sets or clears the pool's boost. Stakers keep their current multiplier
+/// until `refresh_boost` runs for them. While boost tokens are locked, the
+/// boost stays a token boost of the same mint, so that they can be unlocked.
+pub fn process_set_boost(ctx: Context<SetBoost>, boost: Option<BoostConfig>) -> Result<()> {
+    if let Some(boost) = &boost {
+        boost.validate()?;
+    }
+    let pool = &mut ctx.accounts.stake_pool;
+    if pool.boost_locked > 0 {
+        let keeps_mint = matches!(
+            boost,
+            Some(BoostConfig { source: BoostSource::Token { mint, .. }, .. })
+                if pool.boost_lock_mint() == Some(mint)
+        );
+        require!(keeps_mint, StakingError::BoostStillLocked);
+    }
+    pool.boost = boost;
+
+    msg!(
+        "Updated boost, multiplier: {:?} bps",
+        boost.map(|boost| boost.multiplier_bps)
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetBoost<'info> {
+    #[account(
+        constraint = authority.key() == stake_pool.authority @ StakingError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
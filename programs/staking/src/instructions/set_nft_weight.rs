@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    error::StakingError,
+    state::{NftWeight, StakePool},
+};
+
+/// Gives one NFT of an NFT pool its own weight in place of the pool's
+/// default. NFTs already staked keep the weight they were staked with.
+pub fn process_set_nft_weight(ctx: Context<SetNftWeight>, weight: u64) -> Result<()> {
+    require!(weight > 0, StakingError::InvalidNftWeight);
+    let nft_weight = &mut ctx.accounts.nft_weight;
+    nft_weight.weight = weight;
+    nft_weight.bump = ctx.bumps.nft_weight;
+
+    msg!("Set weight of {} to {}", ctx.accounts.nft_mint.key(), weight);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetNftWeight<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == stake_pool.authority @ StakingError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = stake_pool.nft_pool @ StakingError::NotAnNftPool,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + NftWeight::INIT_SPACE,
+        seeds = [b"nft_weight", stake_pool.key().as_ref(), nft_mint.key().as_ref()],
+        bump
+    )]
+    pub nft_weight: Account<'info, NftWeight>,
+    pub system_program: Program<'info, System>,
+}
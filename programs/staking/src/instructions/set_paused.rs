@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::StakingError, state::StakePool};
+
+pub fn process_set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    ctx.accounts.stake_pool.paused = paused;
+
+    msg!("Stake pool paused: {}", paused);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        constraint = authority.key() == stake_pool.authority @ StakingError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
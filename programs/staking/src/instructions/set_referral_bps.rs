@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{
+    constants::BPS_DENOMINATOR,
+    error::StakingError,
+    state::StakePool,
+    utility::{scheduled_rewards, update_pool_rewards},
+};
+
+/// Sets the bonus paid to referrers on top of their referees' rewards, and
+/// the `referral_budget` it is paid from. The budget must already sit in the
+/// reward vault beyond what stakers are owed. The rate applies to rewards as
+/// they are next checkpointed into a user's `pending_rewards`.
+pub fn process_set_referral_bps(
+    ctx: Context<SetReferralBps>,
+    referral_bps: u16,
+    referral_budget: u64,
+) -> Result<()> {
+    require!(
+        referral_bps as u64 <= BPS_DENOMINATOR,
+        StakingError::InvalidReferralBps
+    );
+    require!(
+        referral_bps == 0 || !ctx.accounts.stake_pool.is_points_only(),
+        StakingError::PointsPoolHasNoRewards
+    );
+    require!(
+        referral_bps == 0 || referral_budget > 0,
+        StakingError::ReferralBudgetUnfunded
+    );
+    let current_time = Clock::get()?.unix_timestamp;
+    let pool = &mut ctx.accounts.stake_pool;
+    update_pool_rewards(pool, current_time)?;
+
+    if referral_budget > 0 {
+        let vault = ctx
+            .accounts
+            .pool_reward_vault
+            .as_ref()
+            .ok_or(StakingError::InvalidRewardVault)?;
+        let reserved = pool
+            .rewards_owed
+            .checked_add(scheduled_rewards(pool, current_time)?)
+            .ok_or(StakingError::MathOverflow)?;
+        require!(
+            referral_budget <= vault.amount.saturating_sub(reserved),
+            StakingError::ReferralBudgetUnfunded
+        );
+    }
+    pool.referral_bps = referral_bps;
+    pool.referral_budget = referral_budget;
+
+    msg!(
+        "Updated referral bonus to {} bps, budget: {}",
+        referral_bps,
+        referral_budget
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetReferralBps<'info> {
+    #[account(
+        constraint = authority.key() == stake_pool.authority @ StakingError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    /// Required to fund a budget.
+    #[account(
+        seeds = [b"reward_vault", stake_pool.key().as_ref(), stake_pool.reward_mint.as_ref()],
+        bump
+    )]
+    pub pool_reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::MAX_REWARD_SEGMENTS,
+    error::StakingError,
+    state::{RewardSegment, StakePool},
+    utility::update_pool_rewards,
+};
+
+/// Replaces the pool's upcoming reward rate changes. An empty schedule keeps
+/// the current `reward_rate` indefinitely.
+pub fn process_set_reward_schedule(
+    ctx: Context<SetRewardSchedule>,
+    schedule: Vec<RewardSegment>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.stake_pool;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(
+        schedule.len() <= MAX_REWARD_SEGMENTS,
+        StakingError::InvalidRewardSchedule
+    );
+    require!(
+        schedule
+            .iter()
+            .all(|segment| segment.start_time > current_time),
+        StakingError::InvalidRewardSchedule
+    );
+    require!(
+        schedule
+            .windows(2)
+            .all(|pair| pair[0].start_time < pair[1].start_time),
+        StakingError::InvalidRewardSchedule
+    );
+
+    require!(
+        schedule.is_empty() || !pool.is_points_only(),
+        StakingError::PointsPoolHasNoRewards
+    );
+
+    // settle under the old schedule before replacing it
+    update_pool_rewards(pool, current_time)?;
+    pool.reward_schedule = schedule;
+
+    msg!("Reward schedule set with {} segments", pool.reward_schedule.len());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetRewardSchedule<'info> {
+    #[account(
+        constraint = authority.key() == stake_pool.authority @ StakingError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
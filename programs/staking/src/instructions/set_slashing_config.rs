@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::StakingError, state::StakePool};
+
+pub fn process_set_slashing_config(
+    ctx: Context<SetSlashingConfig>,
+    slasher: Option<Pubkey>,
+    slash_treasury: Option<Pubkey>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.stake_pool;
+    pool.slasher = slasher;
+    pool.slash_treasury = slash_treasury;
+
+    msg!("Updated slashing config, slasher: {:?}, treasury: {:?}", slasher, slash_treasury);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetSlashingConfig<'info> {
+    #[account(
+        constraint = authority.key() == stake_pool.authority @ StakingError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
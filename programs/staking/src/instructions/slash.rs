@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::StakingError;
+use crate::events::StakeSlashed;
+use crate::state::{creator_seed, StakePool, UserStakeAccount};
+use crate::utility::{
+    accrue_user_rewards, record_stake_checkpoints, update_effective_stake, update_pool_rewards,
+};
+
+/// Moves `bps` of `user`'s stake into the slash treasury. Rewards earned up to
+/// now are settled first and stay claimable; every lot is cut by the same share.
+/// Passing the pool as `user` slashes the receipt stake, which cuts every
+/// receipt's value by that share.
+pub fn process_slash(ctx: Context<Slash>, user: Pubkey, bps: u16, reason_code: u16) -> Result<()> {
+    require!(
+        bps > 0 && bps as u64 <= BPS_DENOMINATOR,
+        StakingError::InvalidSlashAmount
+    );
+    let current_time = Clock::get()?.unix_timestamp;
+
+    {
+        let pool = &mut ctx.accounts.stake_pool;
+        update_pool_rewards(pool, current_time)?;
+    }
+    let user_stake = &mut ctx.accounts.user_stake_account;
+    accrue_user_rewards(user_stake, &mut ctx.accounts.stake_pool)?;
+
+    let mut slashed: u64 = 0;
+    if user == ctx.accounts.stake_pool.key() {
+        // the receipt stake keeps no lots
+        slashed = (user_stake.amount_staked as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    }
+    for lot in user_stake.lots.iter_mut() {
+        let cut = (lot.amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        lot.amount -= cut;
+        slashed = slashed
+            .checked_add(cut)
+            .ok_or(StakingError::MathOverflow)?;
+    }
+    user_stake.lots.retain(|lot| lot.amount > 0);
+    require!(slashed > 0, StakingError::NothingStaked);
+
+    let stake_mint_key = ctx.accounts.stake_pool.stake_mint.key();
+    let reward_mint_key = ctx.accounts.stake_pool.reward_mint.key();
+    let community_creator = ctx.accounts.stake_pool.community_creator;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"stake_pool",
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
+        creator_seed(&community_creator),
+        &[ctx.accounts.stake_pool.bump],
+    ]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.pool_stake_vault.to_account_info(),
+                mint: ctx.accounts.stake_mint.to_account_info(),
+                to: ctx.accounts.slash_treasury.to_account_info(),
+                authority: ctx.accounts.stake_pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        slashed,
+        ctx.accounts.stake_mint.decimals,
+    )?;
+
+    //update state
+    user_stake.amount_staked = user_stake
+        .amount_staked
+        .checked_sub(slashed)
+        .ok_or(StakingError::MathOverflow)?;
+    let pool = &mut ctx.accounts.stake_pool;
+    pool.total_staked = pool
+        .total_staked
+        .checked_sub(slashed)
+        .ok_or(StakingError::MathOverflow)?;
+    update_effective_stake(pool, user_stake)?;
+    // only the authority or slasher can cut a stake, and snapshots must see it
+    record_stake_checkpoints(pool, user_stake, true)?;
+
+    emit!(StakeSlashed {
+        pool: pool.key(),
+        user,
+        amount: slashed,
+        reason_code,
+        user_total_staked: user_stake.amount_staked,
+        pool_total_staked: pool.total_staked,
+        reward_per_token_stored: pool.reward_per_token_stored,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct Slash<'info> {
+    #[account(
+        constraint = slasher.key() == stake_pool.authority
+            || stake_pool.slasher == Some(slasher.key()) @ StakingError::Unauthorized
+    )]
+    pub slasher: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = !stake_pool.nft_pool @ StakingError::NftPoolUnsupported
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    /// The user's stake account, or the pool's receipt stake when `user` is
+    /// the pool.
+    #[account(
+        mut,
+        constraint = user_stake_account.stake_pool == stake_pool.key() @ StakingError::StakePoolMismatch,
+        constraint = user_stake_account.user == user @ StakingError::Unauthorized
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+    #[account(
+        mut,
+        seeds = [b"stake_vault", stake_pool.key().as_ref(), stake_pool.stake_mint.as_ref()],
+        bump
+    )]
+    pub pool_stake_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = stake_pool.slash_treasury == Some(slash_treasury.key()) @ StakingError::SlashingNotConfigured,
+    )]
+    pub slash_treasury: InterfaceAccount<'info, TokenAccount>,
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
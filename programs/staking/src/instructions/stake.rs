@@ -3,31 +3,21 @@ use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, Tran
 
 use crate::state::{StakePool, UserStakeAccount};
 use crate::error::StakingError;
-use crate::utility::{calculate_pending_rewards, update_pool_rewards};
+use crate::events::Staked;
+use crate::utility::{add_stake_lot, received_amount, record_deposit};
 
-pub fn process_stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+/// Stakes the user's tokens. `referrer` is recorded on the user's first
+/// stake only and earns the pool's referral bonus on the user's rewards.
+pub fn process_stake(ctx: Context<Stake>, amount: u64, referrer: Option<Pubkey>) -> Result<()> {
     require!(amount > 0, StakingError::InvalidAmount);
+    require!(
+        referrer != Some(ctx.accounts.user.key()),
+        StakingError::SelfReferral
+    );
+    let first_stake = ctx.accounts.user_stake_account.user == Pubkey::default();
 
     let current_time = Clock::get()?.unix_timestamp;
-    //update pool rewards
-    {
-        let pool = &mut ctx.accounts.stake_pool;
-        update_pool_rewards(pool, current_time)?;
-    }
-    let user_stake = &mut ctx.accounts.user_stake_account;
-
-    if user_stake.user == Pubkey::default() {
-        user_stake.user = ctx.accounts.user.key();
-        user_stake.stake_pool = ctx.accounts.stake_pool.key();
-        user_stake.amount_staked = 0;
-        user_stake.pending_rewards = 0;
-        user_stake.stake_start_time = current_time;
-        user_stake.reward_per_token_paid = ctx.accounts.stake_pool.reward_per_token_stored;
-        user_stake.bump = ctx.bumps.user_stake_account;
-    } else {
-        user_stake.pending_rewards = calculate_pending_rewards(user_stake, &ctx.accounts.stake_pool)?;
-        user_stake.reward_per_token_paid = ctx.accounts.stake_pool.reward_per_token_stored;
-    }
+    let vault_balance = ctx.accounts.pool_stake_vault.amount;
 
     token_interface::transfer_checked(
         CpiContext::new(
@@ -40,12 +30,35 @@ pub fn process_stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
             })
         ,amount, ctx.accounts.stake_mint.decimals)?;
 
-    //update state
-    user_stake.amount_staked = user_stake.amount_staked.checked_add(amount).unwrap();
-    let pool = &mut ctx.accounts.stake_pool;
-    pool.total_staked = pool.total_staked.checked_add(amount).unwrap();
+    // credit what the vault received, net of any transfer fee
+    let amount = received_amount(&mut ctx.accounts.pool_stake_vault, vault_balance)?;
+    require!(amount > 0, StakingError::InvalidAmount);
+
+    record_deposit(
+        &mut ctx.accounts.stake_pool,
+        &mut ctx.accounts.user_stake_account,
+        ctx.accounts.user.key(),
+        ctx.accounts.user.key(),
+        ctx.bumps.user_stake_account,
+        amount,
+        current_time,
+    )?;
+    add_stake_lot(&mut ctx.accounts.user_stake_account, amount, current_time, 0)?;
+    if first_stake {
+        ctx.accounts.user_stake_account.referrer = referrer;
+    }
 
-    msg!("Staked {} token. Total staked {}", amount, user_stake.amount_staked);
+    let pool = &ctx.accounts.stake_pool;
+    let user_stake = &ctx.accounts.user_stake_account;
+    emit!(Staked {
+        pool: pool.key(),
+        user: user_stake.user,
+        funder: ctx.accounts.user.key(),
+        amount,
+        user_total_staked: user_stake.amount_staked,
+        pool_total_staked: pool.total_staked,
+        reward_per_token_stored: pool.reward_per_token_stored,
+    });
     
     Ok(())
 }
@@ -56,8 +69,15 @@ pub struct Stake<'info> {
     pub user: Signer<'info>,
     #[account(
         mut,
-        seeds = [b"stake_pool", stake_pool.stake_mint.as_ref(), stake_pool.reward_mint.as_ref()],
-        bump = stake_pool.bump
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = !stake_pool.nft_pool @ StakingError::NftPoolUnsupported,
+        constraint = !stake_pool.paused @ StakingError::PoolPaused,
     )]
     pub stake_pool: Account<'info, StakePool>,
     #[account(
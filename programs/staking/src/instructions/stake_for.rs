@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::constants::MAX_LOCK_DURATION;
+use crate::error::StakingError;
+use crate::events::Staked;
+use crate::state::{StakePool, UserStakeAccount};
+use crate::utility::{add_stake_lot, received_amount, record_deposit};
+
+/// Stakes the funder's tokens into `beneficiary`'s stake account. With
+/// `lock_until` the deposit cannot be withdrawn before then, whatever the
+/// pool's early exit terms. A lock ends at most `MAX_LOCK_DURATION` from now
+/// and cannot take the beneficiary's last free stake lot.
+pub fn process_stake_for(
+    ctx: Context<StakeFor>,
+    beneficiary: Pubkey,
+    amount: u64,
+    lock_until: Option<i64>,
+) -> Result<()> {
+    require!(amount > 0, StakingError::InvalidAmount);
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let unlock_time = lock_until.unwrap_or(0);
+    if unlock_time > current_time {
+        require!(
+            unlock_time - current_time <= MAX_LOCK_DURATION,
+            StakingError::InvalidLockTime
+        );
+    }
+    let vault_balance = ctx.accounts.pool_stake_vault.amount;
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.funder_token_account.to_account_info(),
+                mint: ctx.accounts.stake_mint.to_account_info(),
+                to: ctx.accounts.pool_stake_vault.to_account_info(),
+                authority: ctx.accounts.funder.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.stake_mint.decimals,
+    )?;
+
+    // credit what the vault received, net of any transfer fee
+    let amount = received_amount(&mut ctx.accounts.pool_stake_vault, vault_balance)?;
+    require!(amount > 0, StakingError::InvalidAmount);
+
+    record_deposit(
+        &mut ctx.accounts.stake_pool,
+        &mut ctx.accounts.user_stake_account,
+        beneficiary,
+        ctx.accounts.funder.key(),
+        ctx.bumps.user_stake_account,
+        amount,
+        current_time,
+    )?;
+    add_stake_lot(&mut ctx.accounts.user_stake_account, amount, current_time, unlock_time)?;
+
+    let pool = &ctx.accounts.stake_pool;
+    let user_stake = &ctx.accounts.user_stake_account;
+    emit!(Staked {
+        pool: pool.key(),
+        user: beneficiary,
+        funder: ctx.accounts.funder.key(),
+        amount,
+        user_total_staked: user_stake.amount_staked,
+        pool_total_staked: pool.total_staked,
+        reward_per_token_stored: pool.reward_per_token_stored,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(beneficiary: Pubkey)]
+pub struct StakeFor<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = !stake_pool.nft_pool @ StakingError::NftPoolUnsupported,
+        constraint = !stake_pool.paused @ StakingError::PoolPaused,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = 8 + UserStakeAccount::INIT_SPACE,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), beneficiary.as_ref()],
+        bump,
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+    #[account(
+        mut,
+        constraint = funder_token_account.owner == funder.key(),
+        constraint = funder_token_account.mint == stake_pool.stake_mint,
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"stake_vault", stake_pool.key().as_ref(), stake_pool.stake_mint.as_ref()],
+        bump
+    )]
+    pub pool_stake_vault: InterfaceAccount<'info, TokenAccount>,
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::StakingError;
+use crate::events::LiquidStaked;
+use crate::state::{StakePool, UserStakeAccount};
+use crate::utility::{
+    compound_receipt_stake, open_stake_account, received_amount, receipts_for_deposit,
+    record_stake_checkpoints, update_effective_stake, update_pool_rewards,
+};
+
+/// Stakes the user's tokens into the pool's receipt stake, a stake account
+/// owned by the pool itself, and mints receipts for them at its current
+/// rate. The receipt stake earns rewards like any other and restakes them,
+/// so each receipt is worth a growing amount of stake. It keeps no lots, and
+/// as receipt holders are not tracked, `max_per_user` caps each deposit.
+pub fn process_stake_liquid(ctx: Context<StakeLiquid>, amount: u64) -> Result<()> {
+    require!(amount > 0, StakingError::InvalidAmount);
+    let current_time = Clock::get()?.unix_timestamp;
+
+    update_pool_rewards(&mut ctx.accounts.stake_pool, current_time)?;
+    if ctx.accounts.receipt_stake_account.user == Pubkey::default() {
+        let pool_key = ctx.accounts.stake_pool.key();
+        open_stake_account(
+            &ctx.accounts.stake_pool,
+            &mut ctx.accounts.receipt_stake_account,
+            pool_key,
+            ctx.bumps.receipt_stake_account,
+        );
+    }
+    compound_receipt_stake(
+        &mut ctx.accounts.stake_pool,
+        &mut ctx.accounts.receipt_stake_account,
+        ctx.accounts.pool_reward_vault.as_ref(),
+        &mut ctx.accounts.pool_stake_vault,
+        &ctx.accounts.stake_mint,
+        &ctx.accounts.token_program,
+    )?;
+
+    let vault_balance = ctx.accounts.pool_stake_vault.amount;
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_token_account.to_account_info(),
+                mint: ctx.accounts.stake_mint.to_account_info(),
+                to: ctx.accounts.pool_stake_vault.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.stake_mint.decimals,
+    )?;
+
+    // credit what the vault received, net of any transfer fee
+    let amount = received_amount(&mut ctx.accounts.pool_stake_vault, vault_balance)?;
+    ctx.accounts
+        .stake_pool
+        .limits
+        .check_deposit(amount, 0, ctx.accounts.stake_pool.total_staked)?;
+    let receipts = receipts_for_deposit(
+        amount,
+        ctx.accounts.receipt_stake_account.amount_staked,
+        ctx.accounts.receipt_mint.supply,
+    )?;
+    require!(receipts > 0, StakingError::InvalidAmount);
+
+    let pool = &ctx.accounts.stake_pool;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"stake_pool",
+        pool.stake_mint.as_ref(),
+        pool.reward_mint.as_ref(),
+        pool.creator_seed(),
+        &[pool.bump],
+    ]];
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.receipt_mint.to_account_info(),
+                to: ctx.accounts.user_receipt_account.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        receipts,
+    )?;
+    ctx.accounts.receipt_mint.reload()?;
+
+    //update state
+    let receipt_stake = &mut ctx.accounts.receipt_stake_account;
+    receipt_stake.amount_staked = receipt_stake
+        .amount_staked
+        .checked_add(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    let pool = &mut ctx.accounts.stake_pool;
+    pool.total_staked = pool
+        .total_staked
+        .checked_add(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    update_effective_stake(pool, receipt_stake)?;
+    record_stake_checkpoints(pool, receipt_stake, true)?;
+
+    emit!(LiquidStaked {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        amount,
+        receipts,
+        receipt_staked: receipt_stake.amount_staked,
+        receipt_supply: ctx.accounts.receipt_mint.supply,
+        pool_total_staked: pool.total_staked,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct StakeLiquid<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = !stake_pool.paused @ StakingError::PoolPaused,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    /// The pool's receipt stake, opened by the first deposit.
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStakeAccount::INIT_SPACE,
+        seeds = [b"receipt_stake", stake_pool.key().as_ref()],
+        bump,
+    )]
+    pub receipt_stake_account: Account<'info, UserStakeAccount>,
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == stake_pool.stake_mint,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = stake_pool.receipt_mint == Some(receipt_mint.key()) @ StakingError::InvalidReceiptAccount,
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        constraint = user_receipt_account.owner == user.key() @ StakingError::InvalidReceiptAccount,
+        constraint = user_receipt_account.mint == receipt_mint.key() @ StakingError::InvalidReceiptAccount,
+    )]
+    pub user_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"stake_vault", stake_pool.key().as_ref(), stake_pool.stake_mint.as_ref()],
+        bump
+    )]
+    pub pool_stake_vault: InterfaceAccount<'info, TokenAccount>,
+    /// Required when the receipt stake has rewards to restake.
+    #[account(
+        mut,
+        seeds = [b"reward_vault", stake_pool.key().as_ref(), stake_pool.reward_mint.as_ref()],
+        bump
+    )]
+    pub pool_reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address = stake_pool.stake_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::StakingError;
+use crate::events::NftStaked;
+use crate::state::{NftStakeRecord, NftWeight, StakePool, UserStakeAccount};
+use crate::utility::{check_mint_extensions, is_collection_member, received_amount, record_deposit};
+
+/// Moves one NFT of the pool's collection into custody and stakes its weight:
+/// its `NftWeight` if the authority set one, the pool's default otherwise.
+pub fn process_stake_nft(ctx: Context<StakeNft>) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let nft_mint = &ctx.accounts.nft_mint;
+    require!(
+        is_collection_member(
+            &nft_mint.to_account_info(),
+            ctx.accounts.nft_metadata.as_ref().map(|metadata| metadata.as_ref()),
+            &ctx.accounts.stake_pool.stake_mint,
+        ),
+        StakingError::NotInCollection
+    );
+    check_mint_extensions(nft_mint)?;
+
+    let weight_info = ctx.accounts.nft_weight.to_account_info();
+    let weight = if weight_info.owner == &crate::ID && !weight_info.data_is_empty() {
+        NftWeight::try_deserialize(&mut &weight_info.try_borrow_data()?[..])?.weight
+    } else {
+        ctx.accounts.stake_pool.default_nft_weight
+    };
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_nft_account.to_account_info(),
+                mint: nft_mint.to_account_info(),
+                to: ctx.accounts.nft_vault.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        1,
+        nft_mint.decimals,
+    )?;
+    require!(
+        received_amount(&mut ctx.accounts.nft_vault, 0)? == 1,
+        StakingError::InvalidAmount
+    );
+
+    record_deposit(
+        &mut ctx.accounts.stake_pool,
+        &mut ctx.accounts.user_stake_account,
+        ctx.accounts.user.key(),
+        ctx.accounts.user.key(),
+        ctx.bumps.user_stake_account,
+        weight,
+        current_time,
+    )?;
+
+    let record = &mut ctx.accounts.nft_stake_record;
+    record.owner = ctx.accounts.user.key();
+    record.stake_pool = ctx.accounts.stake_pool.key();
+    record.mint = nft_mint.key();
+    record.weight = weight;
+    record.staked_at = current_time;
+    record.bump = ctx.bumps.nft_stake_record;
+
+    let pool = &ctx.accounts.stake_pool;
+    let user_stake = &ctx.accounts.user_stake_account;
+    emit!(NftStaked {
+        pool: pool.key(),
+        user: user_stake.user,
+        mint: record.mint,
+        weight,
+        user_total_staked: user_stake.amount_staked,
+        pool_total_staked: pool.total_staked,
+        reward_per_token_stored: pool.reward_per_token_stored,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct StakeNft<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = stake_pool.nft_pool @ StakingError::NotAnNftPool,
+        constraint = !stake_pool.paused @ StakingError::PoolPaused,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStakeAccount::INIT_SPACE,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: Metaplex metadata naming the NFT's verified collection, unless
+    /// it is a Token-2022 group member. Parsed in `is_collection_member`.
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        constraint = user_nft_account.owner == user.key(),
+        constraint = user_nft_account.mint == nft_mint.key(),
+    )]
+    pub user_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = user,
+        token::mint = nft_mint,
+        token::authority = stake_pool,
+        token::token_program = token_program,
+        seeds = [b"nft_vault", stake_pool.key().as_ref(), nft_mint.key().as_ref()],
+        bump
+    )]
+    pub nft_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = user,
+        space = 8 + NftStakeRecord::INIT_SPACE,
+        seeds = [b"nft_stake", stake_pool.key().as_ref(), nft_mint.key().as_ref()],
+        bump
+    )]
+    pub nft_stake_record: Account<'info, NftStakeRecord>,
+    /// CHECK: the NFT's `NftWeight`, read only if the authority created it.
+    #[account(
+        seeds = [b"nft_weight", stake_pool.key().as_ref(), nft_mint.key().as_ref()],
+        bump
+    )]
+    pub nft_weight: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
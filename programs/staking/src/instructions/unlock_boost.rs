@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::StakingError;
+use crate::events::BoostUnlocked;
+use crate::state::{creator_seed, StakePool, UserStakeAccount};
+use crate::utility::{apply_boost, token_boost};
+
+/// Returns `amount` of the user's locked boost tokens, dropping the boost
+/// once less than `min_balance` stays locked. Works while the pool is paused.
+pub fn process_unlock_boost(ctx: Context<UnlockBoost>, amount: u64) -> Result<()> {
+    require!(
+        amount > 0 && amount <= ctx.accounts.user_stake_account.boost_locked,
+        StakingError::InvalidAmount
+    );
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let stake_mint_key = ctx.accounts.stake_pool.stake_mint.key();
+    let reward_mint_key = ctx.accounts.stake_pool.reward_mint.key();
+    let community_creator = ctx.accounts.stake_pool.community_creator;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"stake_pool",
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
+        creator_seed(&community_creator),
+        &[ctx.accounts.stake_pool.bump],
+    ]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.boost_vault.to_account_info(),
+                mint: ctx.accounts.boost_mint.to_account_info(),
+                to: ctx.accounts.user_boost_account.to_account_info(),
+                authority: ctx.accounts.stake_pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        ctx.accounts.boost_mint.decimals,
+    )?;
+
+    let user_stake = &mut ctx.accounts.user_stake_account;
+    user_stake.boost_locked -= amount;
+    let pool = &mut ctx.accounts.stake_pool;
+    pool.boost_locked = pool
+        .boost_locked
+        .checked_sub(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    let boost_bps = token_boost(pool.boost.as_ref(), user_stake.boost_locked);
+    apply_boost(pool, user_stake, boost_bps, current_time)?;
+
+    emit!(BoostUnlocked {
+        pool: pool.key(),
+        user: user_stake.user,
+        amount,
+        user_boost_locked: user_stake.boost_locked,
+        boost_bps,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnlockBoost<'info> {
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        // set_boost keeps the token boost's mint while anything is locked
+        constraint = stake_pool.boost_lock_mint() == Some(boost_mint.key()) @ StakingError::BoostNotLockable,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user.key().as_ref()],
+        bump = user_stake_account.bump
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+    #[account(
+        mut,
+        constraint = user_boost_account.owner == user.key(),
+        constraint = user_boost_account.mint == boost_mint.key(),
+    )]
+    pub user_boost_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"boost_vault", stake_pool.key().as_ref(), boost_mint.key().as_ref()],
+        bump
+    )]
+    pub boost_vault: InterfaceAccount<'info, TokenAccount>,
+    pub boost_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -1,8 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
-use crate::state::{StakePool, UserStakeAccount};
+use crate::state::{creator_seed, StakePool, UserStakeAccount};
 use crate::error::StakingError;
-use crate::utility::{calculate_pending_rewards, update_pool_rewards};
+use crate::events::Unstaked;
+use crate::utility::{
+    accrue_user_rewards, distribute_rewards, received_amount,
+    record_stake_checkpoints, update_effective_stake, update_pool_rewards, withdraw_from_lots,
+};
 
 pub fn process_unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
     require!(amount > 0, StakingError::InvalidAmount);
@@ -12,50 +16,110 @@ pub fn process_unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
 
     require!(user_stake.user == ctx.accounts.user.key(), StakingError::Unauthorized);
 
-    let stake_duration = current_time - user_stake.stake_start_time;
-
-    require!(stake_duration >= ctx.accounts.stake_pool.min_stake_duration, StakingError::MinimumStakeDurationNotMet);
-
     require!(user_stake.amount_staked >= amount, StakingError::InsufficientStakeBalance);
 
+    // each lot is checked against the lock on its own
+    let penalty = withdraw_from_lots(user_stake, &ctx.accounts.stake_pool, amount, current_time)?;
+
     {
         let pool = &mut ctx.accounts.stake_pool;
         update_pool_rewards(pool, current_time)?;
     }
 
     // update user's pending rewards
-    user_stake.pending_rewards = calculate_pending_rewards(user_stake, &ctx.accounts.stake_pool)?;
-    user_stake.reward_per_token_paid = ctx.accounts.stake_pool.reward_per_token_stored;
+    accrue_user_rewards(user_stake, &mut ctx.accounts.stake_pool)?;
 
     let stake_mint_key = ctx.accounts.stake_pool.stake_mint.key();
     let reward_mint_key = ctx.accounts.stake_pool.reward_mint.key();
+    let community_creator = ctx.accounts.stake_pool.community_creator;
     let signer_seeds: &[&[&[u8]]] = &[
         &[
             b"stake_pool",
             stake_mint_key.as_ref(),
             reward_mint_key.as_ref(),
+            creator_seed(&community_creator),
             &[ctx.accounts.stake_pool.bump]
         ]
     ];
 
     // transfer tokens from pool to user
     token_interface::transfer_checked(
-        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), 
-        TransferChecked { 
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
             from: ctx.accounts.pool_stake_vault.to_account_info(),
             mint: ctx.accounts.stake_mint.to_account_info(),
             to: ctx.accounts.user_token_account.to_account_info(),
-            authority: ctx.accounts.stake_pool.to_account_info() 
+            authority: ctx.accounts.stake_pool.to_account_info()
         }, signer_seeds),
-        amount, ctx.accounts.stake_mint.decimals)?;
+        amount - penalty, ctx.accounts.stake_mint.decimals)?;
+
+    // penalties go to the treasury, or into the reward vault for the remaining stakers
+    let mut redistributed = 0;
+    if penalty > 0 {
+        let reward_vault_balance = ctx.accounts.pool_reward_vault.as_ref().map_or(0, |vault| vault.amount);
+        let destination = match ctx.accounts.stake_pool.early_exit.treasury {
+            Some(treasury) => ctx
+                .accounts
+                .penalty_treasury
+                .as_ref()
+                .filter(|account| account.key() == treasury)
+                .ok_or(StakingError::InvalidPenaltyAccount)?
+                .to_account_info(),
+            None => ctx
+                .accounts
+                .pool_reward_vault
+                .as_ref()
+                .ok_or(StakingError::InvalidPenaltyAccount)?
+                .to_account_info(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.pool_stake_vault.to_account_info(),
+                    mint: ctx.accounts.stake_mint.to_account_info(),
+                    to: destination,
+                    authority: ctx.accounts.stake_pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            penalty,
+            ctx.accounts.stake_mint.decimals,
+        )?;
+        // only what reaches the reward vault, net of any transfer fee, is owed to stakers
+        if ctx.accounts.stake_pool.early_exit.treasury.is_none() {
+            if let Some(vault) = ctx.accounts.pool_reward_vault.as_mut() {
+                redistributed = received_amount(vault, reward_vault_balance)?;
+            }
+        }
+    }
 
     //Update state
-    user_stake.amount_staked = user_stake.amount_staked.checked_sub(amount).unwrap();
-    
+    user_stake.amount_staked = user_stake
+        .amount_staked
+        .checked_sub(amount)
+        .ok_or(StakingError::MathOverflow)?;
+
     let pool = &mut ctx.accounts.stake_pool;
-    pool.total_staked = pool.total_staked.checked_sub(amount).unwrap();
+    pool.total_staked = pool
+        .total_staked
+        .checked_sub(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    update_effective_stake(pool, user_stake)?;
+    if redistributed > 0 {
+        distribute_rewards(pool, redistributed)?;
+    }
+    record_stake_checkpoints(pool, user_stake, true)?;
 
-    msg!("Unstaked tokens {}. Remaining staked {}", amount, user_stake.amount_staked);
+    emit!(Unstaked {
+        pool: pool.key(),
+        user: user_stake.user,
+        amount,
+        penalty,
+        user_total_staked: user_stake.amount_staked,
+        pool_total_staked: pool.total_staked,
+        reward_per_token_stored: pool.reward_per_token_stored,
+    });
     Ok(())
 }
 
@@ -70,15 +134,21 @@ pub struct Unstake<'info> {
     )]
     pub user_stake_account: Account<'info, UserStakeAccount>,
     #[account(
-        mut, 
+        mut,
         constraint = user_token_account.owner == user.key(),
         constraint = user_token_account.mint == stake_pool.stake_mint,
     )]
     pub user_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
-        seeds = [b"stake_pool", stake_pool.stake_mint.as_ref(), stake_pool.reward_mint.as_ref()],
-        bump = stake_pool.bump
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = !stake_pool.nft_pool @ StakingError::NftPoolUnsupported
     )]
     pub stake_pool: Account<'info, StakePool>,
     #[account(
@@ -87,6 +157,19 @@ pub struct Unstake<'info> {
         bump
     )]
     pub pool_stake_vault: InterfaceAccount<'info, TokenAccount>,
+    /// Required when an early exit penalty is redistributed to stakers.
+    #[account(
+        mut,
+        seeds = [b"reward_vault", stake_pool.key().as_ref(), stake_pool.reward_mint.as_ref()],
+        bump
+    )]
+    pub pool_reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Required when an early exit penalty is sent to the pool's treasury.
+    #[account(
+        mut,
+        constraint = penalty_treasury.mint == stake_pool.stake_mint @ StakingError::InvalidPenaltyAccount,
+    )]
+    pub penalty_treasury: Option<InterfaceAccount<'info, TokenAccount>>,
     pub stake_mint: InterfaceAccount<'info, Mint>,
     pub token_program: Interface<'info, TokenInterface>
 }
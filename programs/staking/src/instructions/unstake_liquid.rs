@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Burn, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::StakingError;
+use crate::events::LiquidUnstaked;
+use crate::state::{creator_seed, StakePool, UserStakeAccount};
+use crate::utility::{
+    compound_receipt_stake, receipt_value, record_stake_checkpoints, update_effective_stake,
+    update_pool_rewards,
+};
+
+/// Burns the holder's `receipts` and pays out their share of the pool's
+/// receipt stake. Any holder can redeem, whoever staked the tokens.
+pub fn process_unstake_liquid(ctx: Context<UnstakeLiquid>, receipts: u64) -> Result<()> {
+    require!(receipts > 0, StakingError::InvalidAmount);
+    let current_time = Clock::get()?.unix_timestamp;
+
+    update_pool_rewards(&mut ctx.accounts.stake_pool, current_time)?;
+    compound_receipt_stake(
+        &mut ctx.accounts.stake_pool,
+        &mut ctx.accounts.receipt_stake_account,
+        ctx.accounts.pool_reward_vault.as_ref(),
+        &mut ctx.accounts.pool_stake_vault,
+        &ctx.accounts.stake_mint,
+        &ctx.accounts.token_program,
+    )?;
+
+    let receipt_supply = ctx.accounts.receipt_mint.supply;
+    let amount = receipt_value(
+        receipts,
+        ctx.accounts.receipt_stake_account.amount_staked,
+        receipt_supply,
+    )?;
+    require!(amount > 0, StakingError::InvalidAmount);
+
+    token_interface::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.receipt_mint.to_account_info(),
+                from: ctx.accounts.holder_receipt_account.to_account_info(),
+                authority: ctx.accounts.holder.to_account_info(),
+            },
+        ),
+        receipts,
+    )?;
+
+    let stake_mint_key = ctx.accounts.stake_pool.stake_mint.key();
+    let reward_mint_key = ctx.accounts.stake_pool.reward_mint.key();
+    let community_creator = ctx.accounts.stake_pool.community_creator;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"stake_pool",
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
+        creator_seed(&community_creator),
+        &[ctx.accounts.stake_pool.bump],
+    ]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.pool_stake_vault.to_account_info(),
+                mint: ctx.accounts.stake_mint.to_account_info(),
+                to: ctx.accounts.holder_token_account.to_account_info(),
+                authority: ctx.accounts.stake_pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        ctx.accounts.stake_mint.decimals,
+    )?;
+
+    //update state
+    let receipt_stake = &mut ctx.accounts.receipt_stake_account;
+    receipt_stake.amount_staked = receipt_stake
+        .amount_staked
+        .checked_sub(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    let pool = &mut ctx.accounts.stake_pool;
+    pool.total_staked = pool
+        .total_staked
+        .checked_sub(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    update_effective_stake(pool, receipt_stake)?;
+    record_stake_checkpoints(pool, receipt_stake, true)?;
+
+    emit!(LiquidUnstaked {
+        pool: pool.key(),
+        holder: ctx.accounts.holder.key(),
+        receipts,
+        amount,
+        receipt_staked: receipt_stake.amount_staked,
+        receipt_supply: receipt_supply - receipts,
+        pool_total_staked: pool.total_staked,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnstakeLiquid<'info> {
+    pub holder: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        seeds = [b"receipt_stake", stake_pool.key().as_ref()],
+        bump = receipt_stake_account.bump
+    )]
+    pub receipt_stake_account: Account<'info, UserStakeAccount>,
+    #[account(
+        mut,
+        constraint = holder_token_account.owner == holder.key(),
+        constraint = holder_token_account.mint == stake_pool.stake_mint,
+    )]
+    pub holder_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = stake_pool.receipt_mint == Some(receipt_mint.key()) @ StakingError::InvalidReceiptAccount,
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        constraint = holder_receipt_account.owner == holder.key() @ StakingError::InvalidReceiptAccount,
+        constraint = holder_receipt_account.mint == receipt_mint.key() @ StakingError::InvalidReceiptAccount,
+    )]
+    pub holder_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"stake_vault", stake_pool.key().as_ref(), stake_pool.stake_mint.as_ref()],
+        bump
+    )]
+    pub pool_stake_vault: InterfaceAccount<'info, TokenAccount>,
+    /// Required when the receipt stake has rewards to restake.
+    #[account(
+        mut,
+        seeds = [b"reward_vault", stake_pool.key().as_ref(), stake_pool.reward_mint.as_ref()],
+        bump
+    )]
+    pub pool_reward_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address = stake_pool.stake_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
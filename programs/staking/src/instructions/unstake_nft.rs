@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::error::StakingError;
+use crate::events::NftUnstaked;
+use crate::state::{creator_seed, NftStakeRecord, StakePool, UserStakeAccount};
+use crate::utility::{
+    accrue_user_rewards, record_stake_checkpoints, update_effective_stake, update_pool_rewards,
+};
+
+/// Returns one staked NFT to its owner and takes its weight off their stake.
+/// Rewards earned so far stay claimable. The NFT's lock is lifted while the
+/// pool is paused, as for `emergency_withdraw`.
+pub fn process_unstake_nft(ctx: Context<UnstakeNft>) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let record = &ctx.accounts.nft_stake_record;
+    let pool_paused = ctx.accounts.stake_pool.paused;
+    require!(
+        pool_paused || current_time - record.staked_at >= ctx.accounts.stake_pool.min_stake_duration,
+        StakingError::MinimumStakeDurationNotMet
+    );
+    let weight = record.weight;
+
+    {
+        let pool = &mut ctx.accounts.stake_pool;
+        update_pool_rewards(pool, current_time)?;
+    }
+    let user_stake = &mut ctx.accounts.user_stake_account;
+    accrue_user_rewards(user_stake, &mut ctx.accounts.stake_pool)?;
+
+    let stake_mint_key = ctx.accounts.stake_pool.stake_mint.key();
+    let reward_mint_key = ctx.accounts.stake_pool.reward_mint.key();
+    let community_creator = ctx.accounts.stake_pool.community_creator;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"stake_pool",
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
+        creator_seed(&community_creator),
+        &[ctx.accounts.stake_pool.bump],
+    ]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.nft_vault.to_account_info(),
+                mint: ctx.accounts.nft_mint.to_account_info(),
+                to: ctx.accounts.user_nft_account.to_account_info(),
+                authority: ctx.accounts.stake_pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        1,
+        ctx.accounts.nft_mint.decimals,
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.nft_vault.to_account_info(),
+            destination: ctx.accounts.user.to_account_info(),
+            authority: ctx.accounts.stake_pool.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    //update state
+    user_stake.amount_staked = user_stake
+        .amount_staked
+        .checked_sub(weight)
+        .ok_or(StakingError::MathOverflow)?;
+    let pool = &mut ctx.accounts.stake_pool;
+    pool.total_staked = pool
+        .total_staked
+        .checked_sub(weight)
+        .ok_or(StakingError::MathOverflow)?;
+    update_effective_stake(pool, user_stake)?;
+    record_stake_checkpoints(pool, user_stake, true)?;
+
+    emit!(NftUnstaked {
+        pool: pool.key(),
+        user: user_stake.user,
+        mint: ctx.accounts.nft_mint.key(),
+        weight,
+        user_total_staked: user_stake.amount_staked,
+        pool_total_staked: pool.total_staked,
+        reward_per_token_stored: pool.reward_per_token_stored,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnstakeNft<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = stake_pool.nft_pool @ StakingError::NotAnNftPool,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user.key().as_ref()],
+        bump = user_stake_account.bump
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"nft_stake", stake_pool.key().as_ref(), nft_mint.key().as_ref()],
+        bump = nft_stake_record.bump,
+        constraint = nft_stake_record.owner == user.key() @ StakingError::Unauthorized,
+    )]
+    pub nft_stake_record: Account<'info, NftStakeRecord>,
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"nft_vault", stake_pool.key().as_ref(), nft_mint.key().as_ref()],
+        bump
+    )]
+    pub nft_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = user_nft_account.owner == user.key(),
+        constraint = user_nft_account.mint == nft_mint.key(),
+    )]
+    pub user_nft_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
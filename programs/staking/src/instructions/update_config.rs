@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_POOL_CREATORS;
+use crate::error::StakingError;
+use crate::state::Config;
+
+/// Replaces the config's admin, creator allowlist and creation fee. Pools
+/// already in the registry keep their `official` flag.
+pub fn process_update_config(
+    ctx: Context<UpdateConfig>,
+    admin: Pubkey,
+    pool_creators: Vec<Pubkey>,
+    creation_fee: Option<u64>,
+    fee_recipient: Pubkey,
+) -> Result<()> {
+    require!(pool_creators.len() <= MAX_POOL_CREATORS, StakingError::TooManyPoolCreators);
+
+    let config = &mut ctx.accounts.config;
+    config.admin = admin;
+    config.pool_creators = pool_creators;
+    config.creation_fee = creation_fee;
+    config.fee_recipient = fee_recipient;
+
+    msg!("Updated config, admin: {}, creation fee: {:?}", admin, creation_fee);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        constraint = admin.key() == config.admin @ StakingError::Unauthorized
+    )]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
@@ -1,8 +1,20 @@
 use anchor_lang::prelude::*;
 
-use crate::{error::StakingError, state::StakePool, utility::update_pool_rewards};
+use crate::{
+    error::StakingError,
+    events::PoolUpdated,
+    state::{EarlyExitConfig, PoolLimits, StakePool},
+    utility::update_pool_rewards,
+};
 
-pub fn process_update_pool(ctx: Context<UpdatePool>, new_reward_rate: Option<u64>, new_min_duration: Option<i64>) -> Result<()> {
+pub fn process_update_pool(
+    ctx: Context<UpdatePool>,
+    new_reward_rate: Option<u64>,
+    new_min_duration: Option<i64>,
+    new_early_exit: Option<EarlyExitConfig>,
+    new_limits: Option<PoolLimits>,
+    new_vesting_duration: Option<i64>,
+) -> Result<()> {
     let pool = &mut ctx.accounts.stake_pool;
     let current_time = Clock::get()?.unix_timestamp;
 
@@ -11,28 +23,74 @@ pub fn process_update_pool(ctx: Context<UpdatePool>, new_reward_rate: Option<u64
 
     // update reward rate if provided
     if let Some(rate) = new_reward_rate {
+        require!(rate == 0 || !pool.is_points_only(), StakingError::PointsPoolHasNoRewards);
         pool.reward_rate = rate;
-        msg!("Updated reward rate to: {}", rate);
     }
     
     // update min_duration if provided
     if let Some(duration) = new_min_duration {
+        require!(
+            duration == 0 || pool.receipt_mint.is_none(),
+            StakingError::ReceiptPoolTermsUnsupported
+        );
         pool.min_stake_duration = duration;
-        msg!("Updated minimum stake duration to: {}", duration);
     }
+
+    // update early exit terms if provided
+    if let Some(early_exit) = new_early_exit {
+        early_exit.validate(&pool.stake_mint, &pool.reward_mint)?;
+        // NFTs cannot be partly withdrawn to pay a penalty
+        require!(
+            early_exit.penalty_bps == 0 || !pool.nft_pool,
+            StakingError::NftPoolUnsupported
+        );
+        pool.early_exit = early_exit;
+    }
+
+    // update deposit limits if provided
+    if let Some(limits) = new_limits {
+        limits.validate()?;
+        pool.limits = limits;
+    }
+
+    // update reward vesting if provided, existing schedules keep their terms
+    if let Some(duration) = new_vesting_duration {
+        require!(duration >= 0, StakingError::InvalidVestingDuration);
+        require!(
+            duration == 0 || pool.receipt_mint.is_none(),
+            StakingError::ReceiptPoolTermsUnsupported
+        );
+        pool.reward_vesting_duration = duration;
+    }
+
+    emit!(PoolUpdated {
+        pool: pool.key(),
+        reward_rate: pool.reward_rate,
+        min_stake_duration: pool.min_stake_duration,
+        early_exit: pool.early_exit,
+        limits: pool.limits,
+        reward_vesting_duration: pool.reward_vesting_duration,
+        pool_total_staked: pool.total_staked,
+        reward_per_token_stored: pool.reward_per_token_stored,
+    });
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct UpdatePool<'info> {
+    // not mut, so a governance PDA can sign through CPI
     #[account(
-        mut, 
         constraint = authority.key() == stake_pool.authority @ StakingError::Unauthorized
     )]
     pub authority: Signer<'info>,
     #[account(
         mut,
-        seeds = [b"stake_pool", stake_pool.stake_mint.as_ref(), stake_pool.reward_mint.as_ref()],
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
         bump = stake_pool.bump
     )]
     pub stake_pool: Account<'info, StakePool>,
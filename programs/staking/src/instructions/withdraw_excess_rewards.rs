@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::StakingError;
+use crate::state::StakePool;
+use crate::utility::{scheduled_rewards, update_pool_rewards};
+
+/// Lets the authority take back reward tokens that are neither owed to
+/// stakers nor reserved for the queued reward schedule or referral bonuses.
+pub fn process_withdraw_excess_rewards(ctx: Context<WithdrawExcessRewards>, amount: u64) -> Result<()> {
+    require!(amount > 0, StakingError::InvalidAmount);
+    let current_time = Clock::get()?.unix_timestamp;
+
+    {
+        let pool = &mut ctx.accounts.stake_pool;
+        update_pool_rewards(pool, current_time)?;
+    }
+    let pool = &ctx.accounts.stake_pool;
+    let reserved = pool
+        .rewards_owed
+        .checked_add(scheduled_rewards(pool, current_time)?)
+        .and_then(|reserved| reserved.checked_add(pool.referral_budget))
+        .ok_or(StakingError::MathOverflow)?;
+    let excess = ctx.accounts.pool_reward_vault.amount.saturating_sub(reserved);
+    require!(amount <= excess, StakingError::InsufficientExcessRewards);
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"stake_pool",
+        pool.stake_mint.as_ref(),
+        pool.reward_mint.as_ref(),
+        pool.creator_seed(),
+        &[pool.bump],
+    ]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.pool_reward_vault.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.stake_pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+
+    msg!("Withdrew {} excess reward tokens", amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawExcessRewards<'info> {
+    #[account(
+        constraint = authority.key() == stake_pool.authority @ StakingError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        seeds = [b"reward_vault", stake_pool.key().as_ref(), stake_pool.reward_mint.as_ref()],
+        bump
+    )]
+    pub pool_reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = destination.mint == stake_pool.reward_mint,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = stake_pool.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
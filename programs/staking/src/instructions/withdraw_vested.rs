@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::StakingError;
+use crate::events::VestedRewardsWithdrawn;
+use crate::state::{creator_seed, StakePool, UserStakeAccount};
+use crate::utility::release_owed_rewards;
+
+pub fn process_withdraw_vested(ctx: Context<WithdrawVested>) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let user_stake = &mut ctx.accounts.user_stake_account;
+
+    let mut amount: u64 = 0;
+    for entry in user_stake.vesting.iter_mut() {
+        let releasable = entry.vested(current_time) - entry.withdrawn;
+        entry.withdrawn += releasable;
+        amount = amount
+            .checked_add(releasable)
+            .ok_or(StakingError::MathOverflow)?;
+    }
+    user_stake
+        .vesting
+        .retain(|entry| entry.withdrawn < entry.amount);
+    require!(amount > 0, StakingError::NothingVested);
+
+    let stake_mint_key = ctx.accounts.stake_pool.stake_mint.key();
+    let reward_mint_key = ctx.accounts.stake_pool.reward_mint.key();
+    let community_creator = ctx.accounts.stake_pool.community_creator;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"stake_pool",
+        stake_mint_key.as_ref(),
+        reward_mint_key.as_ref(),
+        creator_seed(&community_creator),
+        &[ctx.accounts.stake_pool.bump],
+    ]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.pool_reward_vault.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.user_reward_account.to_account_info(),
+                authority: ctx.accounts.stake_pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+    release_owed_rewards(&mut ctx.accounts.stake_pool, amount);
+
+    emit!(VestedRewardsWithdrawn {
+        pool: ctx.accounts.stake_pool.key(),
+        user: user_stake.user,
+        amount,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVested<'info> {
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_pool",
+            stake_pool.stake_mint.as_ref(),
+            stake_pool.reward_mint.as_ref(),
+            stake_pool.creator_seed()
+        ],
+        bump = stake_pool.bump,
+        constraint = !stake_pool.paused @ StakingError::PoolPaused,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        seeds = [b"user_stake", stake_pool.key().as_ref(), user.key().as_ref()],
+        bump = user_stake_account.bump
+    )]
+    pub user_stake_account: Account<'info, UserStakeAccount>,
+    #[account(
+        mut,
+        constraint = user_reward_account.owner == user.key(),
+        constraint = user_reward_account.mint == stake_pool.reward_mint,
+    )]
+    pub user_reward_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"reward_vault", stake_pool.key().as_ref(), stake_pool.reward_mint.as_ref()],
+        bump
+    )]
+    pub pool_reward_vault: InterfaceAccount<'info, TokenAccount>,
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
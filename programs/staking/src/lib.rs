@@ -1,31 +1,115 @@
 use anchor_lang::prelude::*;
+mod constants;
 mod error;
+pub mod events;
 mod instructions;
-mod state;
+pub mod state;
 mod utility;
 use instructions::*;
+use state::{BoostConfig, EarlyExitConfig, PoolLimits, RewardSegment};
 declare_id!("StaKe11111111111111111111111111111111111111");
 
 #[program]
 pub mod staking {
     use super::*;
 
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        pool_creators: Vec<Pubkey>,
+        creation_fee: Option<u64>,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        process_initialize_config(ctx, pool_creators, creation_fee, fee_recipient)
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        admin: Pubkey,
+        pool_creators: Vec<Pubkey>,
+        creation_fee: Option<u64>,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        process_update_config(ctx, admin, pool_creators, creation_fee, fee_recipient)
+    }
+
+    pub fn register_pool(ctx: Context<RegisterPool>) -> Result<()> {
+        process_register_pool(ctx)
+    }
+
     pub fn initialize_pool(
         ctx: Context<InitializePool>,
         reward_rate: u64,
         minimum_stake_duration: i64,
+        early_exit: EarlyExitConfig,
+        limits: PoolLimits,
+        reward_vesting_duration: i64,
+    ) -> Result<()> {
+        process_initialize_pool(
+            ctx,
+            reward_rate,
+            minimum_stake_duration,
+            early_exit,
+            limits,
+            reward_vesting_duration,
+        )
+    }
+
+    pub fn initialize_points_pool(
+        ctx: Context<InitializePointsPool>,
+        minimum_stake_duration: i64,
+        early_exit: EarlyExitConfig,
+        limits: PoolLimits,
+    ) -> Result<()> {
+        process_initialize_points_pool(ctx, minimum_stake_duration, early_exit, limits)
+    }
+
+    pub fn initialize_nft_pool(
+        ctx: Context<InitializeNftPool>,
+        reward_rate: u64,
+        minimum_stake_duration: i64,
+        default_nft_weight: u64,
+        limits: PoolLimits,
+        reward_vesting_duration: i64,
     ) -> Result<()> {
-        process_initialize_pool(ctx, reward_rate, minimum_stake_duration)
+        process_initialize_nft_pool(
+            ctx,
+            reward_rate,
+            minimum_stake_duration,
+            default_nft_weight,
+            limits,
+            reward_vesting_duration,
+        )
     }
 
-    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
-        process_stake(ctx, amount)
+    pub fn stake(ctx: Context<Stake>, amount: u64, referrer: Option<Pubkey>) -> Result<()> {
+        process_stake(ctx, amount, referrer)
+    }
+
+    pub fn stake_for(
+        ctx: Context<StakeFor>,
+        beneficiary: Pubkey,
+        amount: u64,
+        lock_until: Option<i64>,
+    ) -> Result<()> {
+        process_stake_for(ctx, beneficiary, amount, lock_until)
     }
 
     pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
         process_unstake(ctx, amount)
     }
 
+    pub fn set_nft_weight(ctx: Context<SetNftWeight>, weight: u64) -> Result<()> {
+        process_set_nft_weight(ctx, weight)
+    }
+
+    pub fn stake_nft(ctx: Context<StakeNft>) -> Result<()> {
+        process_stake_nft(ctx)
+    }
+
+    pub fn unstake_nft(ctx: Context<UnstakeNft>) -> Result<()> {
+        process_unstake_nft(ctx)
+    }
+
     pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
         process_claim_rewards(ctx)
     }
@@ -34,7 +118,144 @@ pub mod staking {
         ctx: Context<UpdatePool>,
         new_reward_rate: Option<u64>,
         new_min_duration: Option<i64>,
+        new_early_exit: Option<EarlyExitConfig>,
+        new_limits: Option<PoolLimits>,
+        new_vesting_duration: Option<i64>,
     ) -> Result<()> {
-        process_update_pool(ctx, new_reward_rate, new_min_duration)
+        process_update_pool(
+            ctx,
+            new_reward_rate,
+            new_min_duration,
+            new_early_exit,
+            new_limits,
+            new_vesting_duration,
+        )
+    }
+
+    pub fn compound(ctx: Context<Compound>) -> Result<()> {
+        process_compound(ctx)
+    }
+
+    pub fn set_auto_compound(ctx: Context<SetAutoCompound>, enabled: bool) -> Result<()> {
+        process_set_auto_compound(ctx, enabled)
+    }
+
+    pub fn create_receipt_mint(ctx: Context<CreateReceiptMint>) -> Result<()> {
+        process_create_receipt_mint(ctx)
+    }
+
+    pub fn stake_liquid(ctx: Context<StakeLiquid>, amount: u64) -> Result<()> {
+        process_stake_liquid(ctx, amount)
+    }
+
+    pub fn unstake_liquid(ctx: Context<UnstakeLiquid>, receipts: u64) -> Result<()> {
+        process_unstake_liquid(ctx, receipts)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        process_set_paused(ctx, paused)
+    }
+
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+        process_emergency_withdraw(ctx)
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        process_propose_authority(ctx, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        process_accept_authority(ctx)
+    }
+
+    pub fn set_reward_schedule(
+        ctx: Context<SetRewardSchedule>,
+        schedule: Vec<RewardSegment>,
+    ) -> Result<()> {
+        process_set_reward_schedule(ctx, schedule)
+    }
+
+    pub fn close_stake_account(ctx: Context<CloseStakeAccount>) -> Result<()> {
+        process_close_stake_account(ctx)
+    }
+
+    pub fn set_slashing_config(
+        ctx: Context<SetSlashingConfig>,
+        slasher: Option<Pubkey>,
+        slash_treasury: Option<Pubkey>,
+    ) -> Result<()> {
+        process_set_slashing_config(ctx, slasher, slash_treasury)
+    }
+
+    pub fn set_referral_bps(
+        ctx: Context<SetReferralBps>,
+        referral_bps: u16,
+        referral_budget: u64,
+    ) -> Result<()> {
+        process_set_referral_bps(ctx, referral_bps, referral_budget)
+    }
+
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+        process_claim_referral_rewards(ctx)
+    }
+
+    pub fn set_boost(ctx: Context<SetBoost>, boost: Option<BoostConfig>) -> Result<()> {
+        process_set_boost(ctx, boost)
+    }
+
+    pub fn refresh_boost(ctx: Context<RefreshBoost>) -> Result<()> {
+        process_refresh_boost(ctx)
+    }
+
+    pub fn lock_boost(ctx: Context<LockBoost>, amount: u64) -> Result<()> {
+        process_lock_boost(ctx, amount)
+    }
+
+    pub fn unlock_boost(ctx: Context<UnlockBoost>, amount: u64) -> Result<()> {
+        process_unlock_boost(ctx, amount)
+    }
+
+    pub fn slash(ctx: Context<Slash>, user: Pubkey, bps: u16, reason_code: u16) -> Result<()> {
+        process_slash(ctx, user, bps, reason_code)
+    }
+
+    pub fn withdraw_excess_rewards(ctx: Context<WithdrawExcessRewards>, amount: u64) -> Result<()> {
+        process_withdraw_excess_rewards(ctx, amount)
+    }
+
+    pub fn recover_token(ctx: Context<RecoverToken>, amount: u64) -> Result<()> {
+        process_recover_token(ctx, amount)
+    }
+
+    pub fn get_pending_rewards(ctx: Context<GetPendingRewards>) -> Result<u64> {
+        process_get_pending_rewards(ctx)
+    }
+
+    pub fn get_stake_points(ctx: Context<GetStakePoints>) -> Result<u128> {
+        process_get_stake_points(ctx)
+    }
+
+    pub fn get_pool_apr(ctx: Context<GetPoolApr>) -> Result<u64> {
+        process_get_pool_apr(ctx)
+    }
+
+    pub fn get_stake_at(ctx: Context<GetStakeAt>, slot: u64) -> Result<u64> {
+        process_get_stake_at(ctx, slot)
+    }
+
+    pub fn get_total_staked_at(ctx: Context<GetTotalStakedAt>, slot: u64) -> Result<u64> {
+        process_get_total_staked_at(ctx, slot)
+    }
+
+    pub fn withdraw_vested(ctx: Context<WithdrawVested>) -> Result<()> {
+        process_withdraw_vested(ctx)
+    }
+
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        process_migrate_pool(ctx)
+    }
+
+    pub fn migrate_user_stake(ctx: Context<MigrateUserStake>) -> Result<()> {
+        process_migrate_user_stake(ctx)
     }
 }
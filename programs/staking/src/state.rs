@@ -1,7 +1,85 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::{
+    BPS_DENOMINATOR, MAX_BOOST_BPS, MAX_POOL_CREATORS, MAX_REWARD_SEGMENTS, MAX_STAKE_CHECKPOINTS, MAX_STAKE_LOTS,
+    MAX_VESTING_ENTRIES,
+};
+use crate::error::StakingError;
+use reward_math::{PoolRewards, StakerRewards};
+
+/// Program-wide settings, created once by the program's upgrade authority.
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Creators who open pools for free. Their pools, and the admin's, are
+    /// listed as official.
+    #[max_len(MAX_POOL_CREATORS)]
+    pub pool_creators: Vec<Pubkey>,
+    /// Lamports anyone else pays `fee_recipient` to open a pool. `None`
+    /// restricts pool creation to the admin and `pool_creators`.
+    pub creation_fee: Option<u64>,
+    pub fee_recipient: Pubkey,
+    pub bump: u8,
+}
+
+impl Config {
+    /// Whether pools opened by `creator` are free and official.
+    pub fn is_trusted_creator(&self, creator: &Pubkey) -> bool {
+        *creator == self.admin || self.pool_creators.contains(creator)
+    }
+
+    /// The `community_creator` of a pool opened by `creator`. Only official
+    /// pools get the plain `[b"stake_pool", stake_mint, reward_mint]` address.
+    pub fn community_creator(&self, creator: &Pubkey) -> Option<Pubkey> {
+        (!self.is_trusted_creator(creator)).then_some(*creator)
+    }
+}
+
+/// Last seed of a pool's address: the `community_creator`, if any.
+pub fn creator_seed(community_creator: &Option<Pubkey>) -> &[u8] {
+    community_creator.as_ref().map_or(&[], |creator| creator.as_ref())
+}
+
+/// Counts the pools opened under the `Config`. Each pool is listed in a
+/// `PoolEntry` of its own, so the registry never grows; clients walk the
+/// entries from index 0 up to `pool_count`.
 #[account]
 #[derive(InitSpace)]
+pub struct PoolRegistry {
+    pub bump: u8,
+    pub pool_count: u64,
+}
+
+/// Registry listing of one pool, at `[b"pool_entry", index]` with the index
+/// in little-endian bytes. Paid for by the pool's creator.
+#[account]
+#[derive(InitSpace)]
+pub struct PoolEntry {
+    pub pool: Pubkey,
+    /// Position in the registry, oldest first.
+    pub index: u64,
+    pub kind: PoolKind,
+    /// Collection mint for NFT pools.
+    pub stake_mint: Pubkey,
+    /// `Pubkey::default()` for points pools.
+    pub reward_mint: Pubkey,
+    pub creator: Pubkey,
+    /// Opened by the admin or an allowlisted creator.
+    pub official: bool,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum PoolKind {
+    Token,
+    Points,
+    Nft,
+}
+
+#[account]
+#[derive(Default, InitSpace)]
 pub struct StakePool {
     pub authority: Pubkey,
     pub stake_mint: Pubkey,
@@ -11,17 +89,430 @@ pub struct StakePool {
     pub last_updated: i64,
     pub min_stake_duration: i64,
     pub total_staked: u64,
+    pub early_exit: EarlyExitConfig,
+    /// Optional liquid receipt mint. Receipts are shares of the pool's own stake
+    /// account, minted by `stake_liquid` and redeemed by any holder through
+    /// `unstake_liquid` at the rate of its `amount_staked` to the receipt supply.
+    pub receipt_mint: Option<Pubkey>,
+    /// Blocks `stake` and `claim_rewards` while set.
+    pub paused: bool,
+    /// Authority proposed through `propose_authority`, awaiting acceptance.
+    pub pending_authority: Option<Pubkey>,
+    pub limits: PoolLimits,
+    /// Upcoming reward rate changes, ordered by `start_time`. Each one
+    /// replaces `reward_rate` once reached and is then dropped.
+    #[max_len(MAX_REWARD_SEGMENTS)]
+    pub reward_schedule: Vec<RewardSegment>,
+    /// Key allowed to slash besides the authority.
+    pub slasher: Option<Pubkey>,
+    /// Stake-mint token account receiving slashed stake. Slashing is
+    /// disabled while unset.
+    pub slash_treasury: Option<Pubkey>,
+    /// Reward tokens accrued to stakers but not yet paid out, rounded up.
+    pub rewards_owed: u64,
+    /// Seconds over which claimed rewards vest linearly. Zero pays out on claim.
+    pub reward_vesting_duration: i64,
     pub bump: u8,
+    /// Layout version, see `migrate_pool`. New fields go after it.
+    pub version: u8,
+    /// Emissions not yet in `reward_per_token_stored`, carried to the next update.
+    pub reward_per_token_remainder: u64,
+    /// Seconds during which the pool held stake, i.e. stake points per token.
+    pub points_per_token_stored: u64,
+    /// Stake-seconds accrued by all stakers together.
+    pub total_stake_points: u128,
+    /// Recent `total_staked` values by slot.
+    pub stake_history: StakeHistory,
+    /// Holding that multiplies a staker's weight, set through `set_boost`.
+    pub boost: Option<BoostConfig>,
+    /// Sum of every staker's `effective_stake`. Rewards are shared out by
+    /// this rather than `total_staked`; stake points are not boosted.
+    pub total_effective_stake: u64,
+    /// Users stake individual NFTs of the collection in `stake_mint` through
+    /// `stake_nft`, each counting for its weight in `total_staked`.
+    pub nft_pool: bool,
+    /// Weight of NFTs without an `NftWeight` of their own.
+    pub default_nft_weight: u64,
+    /// Bonus paid to a staker's referrer on top of the staker's rewards, in
+    /// bps of those rewards. Paid out of `referral_budget`.
+    pub referral_bps: u16,
+    /// Creator of a pool opened for the creation fee, whose key is part of the
+    /// pool's address. `None` for official pools.
+    pub community_creator: Option<Pubkey>,
+    /// Reward tokens set aside in the reward vault for referral bonuses,
+    /// which stop once it runs out.
+    pub referral_budget: u64,
+    /// Boost tokens held in the boost vault for stakers, see `lock_boost`.
+    pub boost_locked: u64,
+    /// Listed in the pool registry, see `PoolEntry`.
+    pub registered: bool,
 }
 
 #[account]
-#[derive(InitSpace)]
+#[derive(Default, InitSpace)]
 pub struct UserStakeAccount {
     pub user: Pubkey,
     pub stake_pool: Pubkey,
     pub amount_staked: u64,
     pub reward_per_token_paid: u64,
     pub pending_rewards: u64,
-    pub stake_start_time: i64,
+    #[max_len(MAX_STAKE_LOTS)]
+    pub lots: Vec<StakeLot>,
+    /// Lets anyone call `compound` for this account.
+    pub auto_compound: bool,
+    /// Claimed rewards still vesting, released through `withdraw_vested`.
+    #[max_len(MAX_VESTING_ENTRIES)]
+    pub vesting: Vec<VestingEntry>,
     pub bump: u8,
+    /// Layout version, see `migrate_user_stake`. New fields go after it.
+    pub version: u8,
+    /// Fraction of a reward token earned on top of `pending_rewards`, scaled by `PRECISION`.
+    pub reward_remainder: u64,
+    pub points_per_token_paid: u64,
+    /// Stake-seconds accrued up to `points_per_token_paid`. Not transferable.
+    pub stake_points: u128,
+    /// Recent `amount_staked` values by slot.
+    pub stake_history: StakeHistory,
+    /// Boost multiplier applied to `amount_staked`, in bps. `BPS_DENOMINATOR` is no boost.
+    pub boost_bps: u16,
+    /// `amount_staked` times the boost, the weight used for rewards.
+    pub effective_stake: u64,
+    /// Token account checked by `refresh_boost`, chosen by the user.
+    pub boost_account: Option<Pubkey>,
+    /// Wallet credited with the referral bonus, recorded on the first stake.
+    pub referrer: Option<Pubkey>,
+    /// Referral bonus the referrer can claim through `claim_referral_rewards`.
+    /// The account cannot be closed until it is claimed.
+    pub referral_owed: u64,
+    /// `BoostClaim` backing the current collection boost, released when the
+    /// boost lapses.
+    pub boost_claim: Option<Pubkey>,
+    /// Boost tokens locked through `lock_boost`, returned by `unlock_boost`.
+    pub boost_locked: u64,
+}
+
+/// Custody record of an NFT held by an NFT pool, closed on `unstake_nft`.
+#[account]
+#[derive(InitSpace)]
+pub struct NftStakeRecord {
+    pub owner: Pubkey,
+    pub stake_pool: Pubkey,
+    pub mint: Pubkey,
+    /// Weight the NFT was staked with, taken off again on unstake.
+    pub weight: u64,
+    pub staked_at: i64,
+    pub bump: u8,
+}
+
+/// Claim of one staker on a boost holding, so that a holding boosts a single
+/// staker at a time. Keyed by the token account for token boosts and by the
+/// NFT mint for collection boosts. Closed once the boost lapses.
+#[account]
+#[derive(InitSpace)]
+pub struct BoostClaim {
+    pub user_stake: Pubkey,
+    pub bump: u8,
+}
+
+/// Weight of one NFT in an NFT pool, set through `set_nft_weight`.
+#[account]
+#[derive(InitSpace)]
+pub struct NftWeight {
+    pub weight: u64,
+    pub bump: u8,
+}
+
+impl StakePool {
+    /// Reward accounting state, as used by `reward_math`. Rewards are shared by
+    /// effective stake and points by raw stake. Stake changes go through
+    /// `update_effective_stake`.
+    pub fn rewards(&self) -> PoolRewards {
+        PoolRewards {
+            reward_rate: self.reward_rate,
+            reward_per_token_stored: self.reward_per_token_stored,
+            reward_per_token_remainder: self.reward_per_token_remainder,
+            last_updated: self.last_updated,
+            total_staked: self.total_effective_stake,
+            rewards_owed: self.rewards_owed,
+            points_per_token_stored: self.points_per_token_stored,
+            total_stake_points: self.total_stake_points,
+            total_points_staked: self.total_staked,
+        }
+    }
+
+    pub fn set_rewards(&mut self, rewards: PoolRewards) {
+        self.reward_rate = rewards.reward_rate;
+        self.reward_per_token_stored = rewards.reward_per_token_stored;
+        self.reward_per_token_remainder = rewards.reward_per_token_remainder;
+        self.last_updated = rewards.last_updated;
+        self.rewards_owed = rewards.rewards_owed;
+        self.points_per_token_stored = rewards.points_per_token_stored;
+        self.total_stake_points = rewards.total_stake_points;
+    }
+
+    pub fn kind(&self) -> PoolKind {
+        if self.nft_pool {
+            PoolKind::Nft
+        } else if self.is_points_only() {
+            PoolKind::Points
+        } else {
+            PoolKind::Token
+        }
+    }
+
+    pub fn creator_seed(&self) -> &[u8] {
+        creator_seed(&self.community_creator)
+    }
+
+    /// Mint stakers lock for a token boost, if the pool has one.
+    pub fn boost_lock_mint(&self) -> Option<Pubkey> {
+        match self.boost?.source {
+            BoostSource::Token { mint, .. } => Some(mint),
+            BoostSource::Collection { .. } => None,
+        }
+    }
+
+    /// Points pools have no reward mint and only accrue stake points.
+    pub fn is_points_only(&self) -> bool {
+        self.reward_mint == Pubkey::default()
+    }
+}
+
+impl UserStakeAccount {
+    /// Reward accounting state, as used by `reward_math`. Rewards are earned on
+    /// effective stake and points on raw stake.
+    pub fn rewards(&self) -> StakerRewards {
+        StakerRewards {
+            amount_staked: self.effective_stake,
+            reward_per_token_paid: self.reward_per_token_paid,
+            pending_rewards: self.pending_rewards,
+            reward_remainder: self.reward_remainder,
+            points_per_token_paid: self.points_per_token_paid,
+            stake_points: self.stake_points,
+            points_staked: self.amount_staked,
+        }
+    }
+
+    pub fn set_rewards(&mut self, rewards: StakerRewards) {
+        self.reward_per_token_paid = rewards.reward_per_token_paid;
+        self.pending_rewards = rewards.pending_rewards;
+        self.reward_remainder = rewards.reward_remainder;
+        self.points_per_token_paid = rewards.points_per_token_paid;
+        self.stake_points = rewards.stake_points;
+    }
+}
+
+/// Staked balance at the end of `slot`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct StakeCheckpoint {
+    pub slot: u64,
+    pub amount: u64,
+}
+
+/// Ring buffer of the latest balance changes. Once full, each new slot
+/// overwrites the oldest checkpoint, which sits at `next`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Eq, InitSpace)]
+pub struct StakeHistory {
+    #[max_len(MAX_STAKE_CHECKPOINTS)]
+    pub checkpoints: Vec<StakeCheckpoint>,
+    pub next: u8,
+}
+
+impl StakeHistory {
+    /// Records `amount` as the balance at `slot`. Several changes within one
+    /// slot keep a single checkpoint holding the last balance.
+    pub fn record(&mut self, slot: u64, amount: u64) {
+        let len = self.checkpoints.len();
+        if len > 0 {
+            let newest = &mut self.checkpoints[(self.next as usize + len - 1) % len];
+            if newest.slot == slot {
+                newest.amount = amount;
+                return;
+            }
+        }
+        let checkpoint = StakeCheckpoint { slot, amount };
+        if len < MAX_STAKE_CHECKPOINTS {
+            self.checkpoints.push(checkpoint);
+        } else {
+            self.checkpoints[self.next as usize] = checkpoint;
+            self.next = ((self.next as usize + 1) % MAX_STAKE_CHECKPOINTS) as u8;
+        }
+    }
+
+    /// Balance at the end of `slot`. Before the first checkpoint the balance
+    /// is 0 while the history still has room; once it is full, and older
+    /// checkpoints may have been overwritten, slots older than every
+    /// checkpoint still held fail with `CheckpointUnavailable`.
+    pub fn balance_at(&self, slot: u64) -> Result<u64> {
+        let (newer, older) = self.checkpoints.split_at(self.next as usize);
+        let checkpoint = older
+            .iter()
+            .chain(newer)
+            .take_while(|checkpoint| checkpoint.slot <= slot)
+            .last();
+        match checkpoint {
+            Some(checkpoint) => Ok(checkpoint.amount),
+            None if !self.is_full() => Ok(0),
+            None => err!(StakingError::CheckpointUnavailable),
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.checkpoints.len() >= MAX_STAKE_CHECKPOINTS
+    }
+}
+
+/// Holding that earns a boost.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum BoostSource {
+    /// At least `min_balance` of `mint` locked through `lock_boost`.
+    Token { mint: Pubkey, min_balance: u64 },
+    /// An NFT in `collection`, by Token-2022 group membership or as a
+    /// verified Metaplex collection.
+    Collection { collection: Pubkey },
+}
+
+/// Multiplies the effective stake of users holding `source`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct BoostConfig {
+    pub source: BoostSource,
+    /// Multiplier in bps, from `BPS_DENOMINATOR` (1x) to `MAX_BOOST_BPS`.
+    pub multiplier_bps: u16,
+}
+
+impl BoostConfig {
+    pub fn validate(&self) -> Result<()> {
+        if let BoostSource::Token { min_balance, .. } = self.source {
+            require!(min_balance > 0, StakingError::InvalidAmount);
+        }
+        require!(
+            self.multiplier_bps as u64 >= BPS_DENOMINATOR
+                && self.multiplier_bps <= MAX_BOOST_BPS,
+            StakingError::InvalidBoostMultiplier
+        );
+        Ok(())
+    }
+}
+
+/// Rewards from one claim, vesting linearly from `start_time` to `end_time`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct VestingEntry {
+    pub amount: u64,
+    pub withdrawn: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+impl VestingEntry {
+    pub fn vested(&self, current_time: i64) -> u64 {
+        if current_time >= self.end_time {
+            return self.amount;
+        }
+        if current_time <= self.start_time {
+            return 0;
+        }
+        let elapsed = (current_time - self.start_time) as u128;
+        let duration = (self.end_time - self.start_time) as u128;
+        (self.amount as u128 * elapsed / duration) as u64
+    }
+}
+
+/// `reward_rate` that takes effect at `start_time`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct RewardSegment {
+    pub start_time: i64,
+    pub reward_rate: u64,
+}
+
+/// Deposit limits enforced on new tokens by `stake`, `stake_for` and
+/// `stake_liquid`, but not on compounded rewards. `None` leaves a cap off.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub struct PoolLimits {
+    pub max_total_staked: Option<u64>,
+    pub max_per_user: Option<u64>,
+    pub min_stake_amount: u64,
+}
+
+impl PoolLimits {
+    /// Rejects limits no deposit could meet: a per-user cap above the pool
+    /// cap, or a minimum above either.
+    pub fn validate(&self) -> Result<()> {
+        if let (Some(max_per_user), Some(max_total_staked)) =
+            (self.max_per_user, self.max_total_staked)
+        {
+            require!(
+                max_per_user <= max_total_staked,
+                StakingError::InvalidPoolLimits
+            );
+        }
+        require!(
+            self.max_per_user
+                .into_iter()
+                .chain(self.max_total_staked)
+                .all(|cap| self.min_stake_amount <= cap),
+            StakingError::InvalidPoolLimits
+        );
+        Ok(())
+    }
+
+    /// Checks a deposit of `amount` on top of the given user and pool totals.
+    pub fn check_deposit(&self, amount: u64, user_staked: u64, total_staked: u64) -> Result<()> {
+        require!(
+            amount >= self.min_stake_amount,
+            StakingError::StakeBelowMinimum
+        );
+        if let Some(max_per_user) = self.max_per_user {
+            require!(
+                user_staked.saturating_add(amount) <= max_per_user,
+                StakingError::UserStakeLimitExceeded
+            );
+        }
+        if let Some(max_total_staked) = self.max_total_staked {
+            require!(
+                total_staked.saturating_add(amount) <= max_total_staked,
+                StakingError::PoolCapacityExceeded
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A deposit whose lock runs from its own `start_time`, oldest first.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct StakeLot {
+    pub amount: u64,
+    pub start_time: i64,
+    /// Hard lock set by a `stake_for` funder. Neither the penalty nor
+    /// `emergency_withdraw` can release the lot before this time, and no
+    /// other deposit is merged into it.
+    pub unlock_time: i64,
+}
+
+/// Terms for unstaking before `min_stake_duration` has elapsed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub struct EarlyExitConfig {
+    /// Penalty charged on the withdrawn amount. Zero keeps the hard lock.
+    pub penalty_bps: u16,
+    /// Scale the penalty down linearly to zero as the lock runs out.
+    pub linear_decay: bool,
+    /// Stake-mint token account receiving penalties. `None` redistributes
+    /// them to the remaining stakers, which requires `stake_mint == reward_mint`.
+    pub treasury: Option<Pubkey>,
+}
+
+impl EarlyExitConfig {
+    pub fn validate(&self, stake_mint: &Pubkey, reward_mint: &Pubkey) -> Result<()> {
+        require!(
+            self.penalty_bps as u64 <= BPS_DENOMINATOR,
+            StakingError::InvalidPenalty
+        );
+        if self.penalty_bps > 0 && self.treasury.is_none() {
+            require_keys_eq!(
+                *stake_mint,
+                *reward_mint,
+                StakingError::PenaltyRedistributionUnsupported
+            );
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,64 @@
+use crate::constants::BPS_DENOMINATOR;
+use crate::state::{BoostConfig, BoostSource, StakePool, UserStakeAccount};
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+use anchor_spl::token_2022::spl_token_2022::extension::StateWithExtensions;
+use anchor_spl::token_2022::{self, spl_token_2022};
+
+use super::{accrue_user_rewards, is_collection_member, update_effective_stake, update_pool_rewards};
+
+/// Boost in bps that `locked` boost tokens earn under the pool's token boost,
+/// or no boost (`BPS_DENOMINATOR`) when they fall short or the pool has none.
+pub fn token_boost(config: Option<&BoostConfig>, locked: u64) -> u16 {
+    match config {
+        Some(BoostConfig {
+            source: BoostSource::Token { min_balance, .. },
+            multiplier_bps,
+        }) if locked >= *min_balance => *multiplier_bps,
+        _ => BPS_DENOMINATOR as u16,
+    }
+}
+
+/// Boost in bps that holding the NFT `mint` in `token_account` earns `user`
+/// under a collection boost, or no boost when it does not qualify. Metaplex
+/// NFTs also need their `metadata`. All are parsed by hand so that a closed
+/// or emptied account lapses the boost instead of failing.
+pub fn collection_boost(
+    config: &BoostConfig,
+    user: &Pubkey,
+    token_account: &AccountInfo,
+    mint: &AccountInfo,
+    metadata: Option<&AccountInfo>,
+) -> Result<u16> {
+    let no_boost = BPS_DENOMINATOR as u16;
+    let BoostSource::Collection { collection } = config.source else {
+        return Ok(no_boost);
+    };
+    if *token_account.owner != token::ID && *token_account.owner != token_2022::ID {
+        return Ok(no_boost);
+    }
+    let data = token_account.try_borrow_data()?;
+    let Ok(holding) = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data) else {
+        return Ok(no_boost);
+    };
+    if holding.base.owner != *user {
+        return Ok(no_boost);
+    }
+    let qualifies = holding.base.amount > 0
+        && mint.key() == holding.base.mint
+        && is_collection_member(mint, metadata, &collection);
+    Ok(if qualifies { config.multiplier_bps } else { no_boost })
+}
+
+/// Settles the user's rewards at their old weight, then applies `boost_bps`.
+pub fn apply_boost(
+    pool: &mut StakePool,
+    user_stake: &mut UserStakeAccount,
+    boost_bps: u16,
+    current_time: i64,
+) -> Result<()> {
+    update_pool_rewards(pool, current_time)?;
+    accrue_user_rewards(user_stake, pool)?;
+    user_stake.boost_bps = boost_bps;
+    update_effective_stake(pool, user_stake)
+}
@@ -0,0 +1,20 @@
+use crate::state::{StakePool, UserStakeAccount};
+use anchor_lang::prelude::*;
+
+/// Adds the current `amount_staked` and `total_staked` to the balance
+/// histories. Call after every change to either. Without `may_evict`, as for
+/// deposits someone else makes for the owner, the user's history is only
+/// written while it has room, so such deposits cannot push the owner's
+/// checkpoints out. The owner's next change records them.
+pub fn record_stake_checkpoints(
+    pool: &mut StakePool,
+    user_stake: &mut UserStakeAccount,
+    may_evict: bool,
+) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    if may_evict || !user_stake.stake_history.is_full() {
+        user_stake.stake_history.record(slot, user_stake.amount_staked);
+    }
+    pool.stake_history.record(slot, pool.total_staked);
+    Ok(())
+}
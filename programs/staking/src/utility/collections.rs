@@ -0,0 +1,130 @@
+use crate::constants::METADATA_PROGRAM_ID;
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_2022::{self, spl_token_2022};
+use spl_token_group_interface::state::{TokenGroup, TokenGroupMember};
+
+/// `Key::MetadataV1` of a Metaplex metadata account.
+const METADATA_V1: u8 = 4;
+
+/// Whether `mint` can stand for an NFT collection: a Token-2022 mint carrying
+/// the token group extension, or a Metaplex collection NFT, whose `metadata`
+/// is then needed as well.
+pub fn is_collection(mint: &AccountInfo, metadata: Option<&AccountInfo>) -> bool {
+    if *mint.owner == token_2022::ID {
+        let Ok(data) = mint.try_borrow_data() else {
+            return false;
+        };
+        if StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)
+            .is_ok_and(|state| state.get_extension::<TokenGroup>().is_ok())
+        {
+            return true;
+        }
+    }
+    metadata
+        .and_then(read_metadata)
+        .is_some_and(|(metadata_mint, _)| metadata_mint == mint.key())
+}
+
+/// Whether `mint` is an NFT (supply 1, no decimals) in `collection`: either
+/// its Token-2022 group member extension names the collection, or its
+/// Metaplex `metadata` names it as a verified collection.
+pub fn is_collection_member(
+    mint: &AccountInfo,
+    metadata: Option<&AccountInfo>,
+    collection: &Pubkey,
+) -> bool {
+    if *mint.owner != token::ID && *mint.owner != token_2022::ID {
+        return false;
+    }
+    let Ok(data) = mint.try_borrow_data() else {
+        return false;
+    };
+    let Ok(state) = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data) else {
+        return false;
+    };
+    if state.base.supply != 1 || state.base.decimals != 0 {
+        return false;
+    }
+    let in_group = state
+        .get_extension::<TokenGroupMember>()
+        .is_ok_and(|member| member.group == *collection && member.mint == mint.key());
+    in_group
+        || metadata.and_then(read_metadata).is_some_and(|(metadata_mint, verified)| {
+            metadata_mint == mint.key() && verified == Some(*collection)
+        })
+}
+
+/// Mint and verified collection of a Metaplex metadata account, read by hand
+/// up to the collection field. `None` for any other account.
+fn read_metadata(metadata: &AccountInfo) -> Option<(Pubkey, Option<Pubkey>)> {
+    if *metadata.owner != METADATA_PROGRAM_ID {
+        return None;
+    }
+    let data = metadata.try_borrow_data().ok()?;
+    let mut reader = BorshReader(&data);
+    if reader.u8()? != METADATA_V1 {
+        return None;
+    }
+    reader.take(32)?; // update authority
+    let mint = reader.pubkey()?;
+    for _ in 0..3 {
+        reader.string()?; // name, symbol, uri
+    }
+    reader.take(2)?; // seller fee
+    if reader.u8()? == 1 {
+        let creators = reader.u32()? as usize;
+        reader.take(creators.checked_mul(34)?)?;
+    }
+    reader.take(2)?; // primary sale, mutable
+    reader.option(1)?; // edition nonce
+    reader.option(1)?; // token standard
+    let collection = match reader.u8()? {
+        1 => {
+            let verified = reader.u8()? == 1;
+            let key = reader.pubkey()?;
+            verified.then_some(key)
+        }
+        _ => None,
+    };
+    Some((mint, collection))
+}
+
+struct BorshReader<'a>(&'a [u8]);
+
+impl<'a> BorshReader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn pubkey(&mut self) -> Option<Pubkey> {
+        Pubkey::try_from(self.take(32)?).ok()
+    }
+
+    fn string(&mut self) -> Option<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    /// Skips an option holding `len` bytes when set.
+    fn option(&mut self, len: usize) -> Option<()> {
+        if self.u8()? == 1 {
+            self.take(len)?;
+        }
+        Some(())
+    }
+}
@@ -0,0 +1,69 @@
+use crate::constants::{BPS_DENOMINATOR, USER_STAKE_VERSION};
+use crate::error::StakingError;
+use crate::state::{StakePool, UserStakeAccount};
+use anchor_lang::prelude::*;
+
+use super::{accrue_user_rewards, record_stake_checkpoints, update_effective_stake, update_pool_rewards};
+
+/// Books a deposit of `amount` by `funder` for `owner`: settles rewards,
+/// checks the pool limits and opens the stake account on first use. The
+/// caller moves the tokens and records the lot, or the NFT, that the deposit
+/// is made of.
+pub fn record_deposit(
+    pool: &mut Account<StakePool>,
+    user_stake: &mut UserStakeAccount,
+    owner: Pubkey,
+    funder: Pubkey,
+    bump: u8,
+    amount: u64,
+    current_time: i64,
+) -> Result<()> {
+    //update pool rewards
+    update_pool_rewards(pool, current_time)?;
+    pool.limits
+        .check_deposit(amount, user_stake.amount_staked, pool.total_staked)?;
+
+    if user_stake.user == Pubkey::default() {
+        open_stake_account(pool, user_stake, owner, bump);
+    } else {
+        accrue_user_rewards(user_stake, pool)?;
+    }
+
+    //update state
+    user_stake.amount_staked = user_stake
+        .amount_staked
+        .checked_add(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    pool.total_staked = pool
+        .total_staked
+        .checked_add(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    update_effective_stake(pool, user_stake)?;
+    record_stake_checkpoints(pool, user_stake, funder == owner)
+}
+
+/// Sets up a freshly created stake account for `owner`, starting from the
+/// pool's current accumulators. Expects `update_pool_rewards` to have run.
+pub fn open_stake_account(
+    pool: &Account<StakePool>,
+    user_stake: &mut UserStakeAccount,
+    owner: Pubkey,
+    bump: u8,
+) {
+    user_stake.user = owner;
+    user_stake.stake_pool = pool.key();
+    user_stake.amount_staked = 0;
+    user_stake.pending_rewards = 0;
+    user_stake.reward_per_token_paid = pool.reward_per_token_stored;
+    user_stake.reward_remainder = 0;
+    user_stake.points_per_token_paid = pool.points_per_token_stored;
+    user_stake.stake_points = 0;
+    user_stake.boost_bps = BPS_DENOMINATOR as u16;
+    user_stake.effective_stake = 0;
+    user_stake.boost_account = None;
+    user_stake.referrer = None;
+    user_stake.referral_owed = 0;
+    user_stake.boost_claim = None;
+    user_stake.bump = bump;
+    user_stake.version = USER_STAKE_VERSION;
+}
@@ -0,0 +1,100 @@
+use crate::constants::{BPS_DENOMINATOR, MAX_STAKE_LOTS};
+use crate::error::StakingError;
+use crate::state::{StakeLot, StakePool, UserStakeAccount};
+use anchor_lang::prelude::*;
+
+/// Penalty owed for withdrawing `amount` after `stake_duration` seconds.
+/// Fails with `MinimumStakeDurationNotMet` while the lock is active and the
+/// pool has no early-exit penalty configured.
+pub fn calculate_early_exit_penalty(
+    pool: &StakePool,
+    amount: u64,
+    stake_duration: i64,
+) -> Result<u64> {
+    if stake_duration >= pool.min_stake_duration {
+        return Ok(0);
+    }
+    let config = &pool.early_exit;
+    require!(
+        config.penalty_bps > 0,
+        StakingError::MinimumStakeDurationNotMet
+    );
+
+    let mut penalty_bps = config.penalty_bps as u128;
+    if config.linear_decay {
+        let remaining = (pool.min_stake_duration - stake_duration.max(0)) as u128;
+        penalty_bps = penalty_bps * remaining / pool.min_stake_duration as u128;
+    }
+    let penalty = amount as u128 * penalty_bps / BPS_DENOMINATOR as u128;
+    Ok(penalty as u64)
+}
+
+/// Records a deposit as a new lot. Once every slot is taken the deposit is
+/// folded into the newest lot without a hard lock, whose start moves to the
+/// amount-weighted average of both. Merging thus cannot shorten a lock, and a
+/// small deposit barely moves it. Hard-locked deposits always need a lot of
+/// their own, and never the last one, so that a funder cannot lock every lot
+/// and leave nothing for later deposits to fold into.
+pub fn add_stake_lot(
+    user_stake: &mut UserStakeAccount,
+    amount: u64,
+    current_time: i64,
+    unlock_time: i64,
+) -> Result<()> {
+    let free_lots = MAX_STAKE_LOTS - user_stake.lots.len();
+    if free_lots > 1 || (free_lots == 1 && unlock_time <= current_time) {
+        user_stake.lots.push(StakeLot {
+            amount,
+            start_time: current_time,
+            unlock_time,
+        });
+        return Ok(());
+    }
+    require!(unlock_time <= current_time, StakingError::StakeLotsFull);
+    let lot = user_stake
+        .lots
+        .iter_mut()
+        .rev()
+        .find(|lot| lot.unlock_time <= current_time)
+        .ok_or(StakingError::StakeLotsFull)?;
+    let total = lot
+        .amount
+        .checked_add(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    // rounded up, towards the later start
+    let weighted = lot.start_time as i128 * lot.amount as i128 + current_time as i128 * amount as i128;
+    lot.start_time = (weighted + total as i128 - 1).div_euclid(total as i128) as i64;
+    lot.amount = total;
+    Ok(())
+}
+
+/// Takes `amount` out of the oldest lots first, skipping hard-locked ones,
+/// and returns the early exit penalty owed on whatever part is still locked.
+pub fn withdraw_from_lots(
+    user_stake: &mut UserStakeAccount,
+    pool: &StakePool,
+    amount: u64,
+    current_time: i64,
+) -> Result<u64> {
+    let mut remaining = amount;
+    let mut penalty: u64 = 0;
+    for lot in user_stake.lots.iter_mut() {
+        if remaining == 0 {
+            break;
+        }
+        if lot.unlock_time > current_time {
+            continue;
+        }
+        let taken = remaining.min(lot.amount);
+        let lot_penalty =
+            calculate_early_exit_penalty(pool, taken, current_time - lot.start_time)?;
+        penalty = penalty
+            .checked_add(lot_penalty)
+            .ok_or(StakingError::MathOverflow)?;
+        lot.amount -= taken;
+        remaining -= taken;
+    }
+    require!(remaining == 0, StakingError::StakeLocked);
+    user_stake.lots.retain(|lot| lot.amount > 0);
+    Ok(penalty)
+}
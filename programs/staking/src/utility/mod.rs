@@ -0,0 +1,22 @@
+pub mod rewards;
+pub use rewards::*;
+pub mod deposits;
+pub use deposits::*;
+pub mod lots;
+pub use lots::*;
+pub mod vesting;
+pub use vesting::*;
+pub mod checkpoints;
+pub use checkpoints::*;
+pub mod boosts;
+pub use boosts::*;
+pub mod collections;
+pub use collections::*;
+pub mod tokens;
+pub use tokens::*;
+pub mod receipts;
+pub use receipts::*;
+pub mod registry;
+pub use registry::*;
+pub mod migration;
+pub use migration::*;
@@ -0,0 +1,89 @@
+use crate::error::StakingError;
+use crate::state::{StakePool, UserStakeAccount};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use super::{accrue_user_rewards, received_amount, release_owed_rewards, update_effective_stake};
+
+/// Receipts minted for depositing `amount` into the pool's receipt stake,
+/// rounded down. The first deposit into an empty one is minted 1:1.
+pub fn receipts_for_deposit(amount: u64, receipt_staked: u64, receipt_supply: u64) -> Result<u64> {
+    if receipt_supply == 0 {
+        return Ok(amount);
+    }
+    // receipts still out against an emptied receipt stake cannot price new ones
+    let receipts = (amount as u128 * receipt_supply as u128)
+        .checked_div(receipt_staked as u128)
+        .ok_or(StakingError::MathOverflow)?;
+    u64::try_from(receipts).map_err(|_| error!(StakingError::MathOverflow))
+}
+
+/// Stake tokens `receipts` redeem for out of the pool's receipt stake,
+/// rounded down.
+pub fn receipt_value(receipts: u64, receipt_staked: u64, receipt_supply: u64) -> Result<u64> {
+    let amount = (receipts as u128 * receipt_staked as u128)
+        .checked_div(receipt_supply as u128)
+        .ok_or(StakingError::MathOverflow)?;
+    u64::try_from(amount).map_err(|_| error!(StakingError::MathOverflow))
+}
+
+/// Restakes the receipt stake's rewards, so that receipts are minted and
+/// redeemed at a rate that includes them. Receipt pools pay rewards in the
+/// stake mint and do not vest them. While the pool is paused, rewards stay
+/// pending like everyone else's, and so does whatever the reward vault is
+/// short of, so that receipts stay redeemable. Call after
+/// `update_pool_rewards`.
+pub fn compound_receipt_stake<'info>(
+    pool: &mut Account<'info, StakePool>,
+    receipt_stake: &mut UserStakeAccount,
+    pool_reward_vault: Option<&InterfaceAccount<'info, TokenAccount>>,
+    pool_stake_vault: &mut InterfaceAccount<'info, TokenAccount>,
+    stake_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    accrue_user_rewards(receipt_stake, pool)?;
+    if receipt_stake.pending_rewards == 0 || pool.paused {
+        return Ok(());
+    }
+    let reward_vault = pool_reward_vault.ok_or(StakingError::InvalidRewardVault)?;
+    let amount = receipt_stake.pending_rewards.min(reward_vault.amount);
+    if amount == 0 {
+        return Ok(());
+    }
+    let vault_balance = pool_stake_vault.amount;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"stake_pool",
+        pool.stake_mint.as_ref(),
+        pool.reward_mint.as_ref(),
+        pool.creator_seed(),
+        &[pool.bump],
+    ]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: reward_vault.to_account_info(),
+                mint: stake_mint.to_account_info(),
+                to: pool_stake_vault.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        stake_mint.decimals,
+    )?;
+    // the full reward leaves the reward vault, only what arrives is staked
+    let staked = received_amount(pool_stake_vault, vault_balance)?;
+
+    receipt_stake.pending_rewards -= amount;
+    receipt_stake.amount_staked = receipt_stake
+        .amount_staked
+        .checked_add(staked)
+        .ok_or(StakingError::MathOverflow)?;
+    release_owed_rewards(pool, amount);
+    pool.total_staked = pool
+        .total_staked
+        .checked_add(staked)
+        .ok_or(StakingError::MathOverflow)?;
+    update_effective_stake(pool, receipt_stake)
+}
@@ -0,0 +1,62 @@
+use crate::error::StakingError;
+use crate::state::{Config, PoolEntry, PoolRegistry, StakePool};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+/// Charges `creator` for opening a pool under `config` and returns whether
+/// the pool is official. Trusted creators open official pools for free;
+/// anyone else pays `creation_fee`, or cannot open pools while none is set.
+pub fn charge_creation_fee<'info>(
+    config: &Config,
+    creator: &Signer<'info>,
+    fee_recipient: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<bool> {
+    let official = config.is_trusted_creator(&creator.key());
+    if !official {
+        let fee = config
+            .creation_fee
+            .ok_or(StakingError::PoolCreationNotAllowed)?;
+        if fee > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    Transfer {
+                        from: creator.to_account_info(),
+                        to: fee_recipient.clone(),
+                    },
+                ),
+                fee,
+            )?;
+        }
+    }
+    Ok(official)
+}
+
+/// Lists `pool` in `entry` as the registry's next pool.
+pub fn add_registry_entry(
+    registry: &mut PoolRegistry,
+    entry: &mut PoolEntry,
+    pool: &mut Account<StakePool>,
+    creator: Pubkey,
+    official: bool,
+    bump: u8,
+) -> Result<()> {
+    *entry = PoolEntry {
+        pool: pool.key(),
+        index: registry.pool_count,
+        kind: pool.kind(),
+        stake_mint: pool.stake_mint,
+        reward_mint: pool.reward_mint,
+        creator,
+        official,
+        registered_at: Clock::get()?.unix_timestamp,
+        bump,
+    };
+    pool.registered = true;
+    registry.pool_count = registry
+        .pool_count
+        .checked_add(1)
+        .ok_or(StakingError::MathOverflow)?;
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use crate::constants::{BPS_DENOMINATOR, PRECISION, STAKE_POOL_VERSION, USER_STAKE_VERSION};
+use crate::error::StakingError;
+use crate::state::{StakePool, UserStakeAccount};
+use anchor_lang::prelude::*;
+
+/// Brings the accumulator up to `current_time`, switching rates at every
+/// scheduled segment boundary on the way.
+pub fn update_pool_rewards(pool: &mut StakePool, current_time: i64) -> Result<()> {
+    require!(pool.version == STAKE_POOL_VERSION, StakingError::AccountNotMigrated);
+    let mut rewards = pool.rewards();
+    let schedule = pool.reward_schedule.iter().map(|segment| reward_math::RewardSegment {
+        start_time: segment.start_time,
+        reward_rate: segment.reward_rate,
+    });
+    let applied = reward_math::update_pool_rewards(&mut rewards, schedule, current_time)
+        .map_err(StakingError::from)?;
+    pool.reward_schedule.drain(..applied);
+    pool.set_rewards(rewards);
+    Ok(())
+}
+
+pub fn calculate_pending_rewards(user_stake: &UserStakeAccount, pool: &StakePool) -> Result<u64> {
+    require!(user_stake.version == USER_STAKE_VERSION, StakingError::AccountNotMigrated);
+    Ok(reward_math::calculate_pending_rewards(&user_stake.rewards(), pool.reward_per_token_stored)
+        .map_err(StakingError::from)?)
+}
+
+/// Moves rewards earned since the last checkpoint into `pending_rewards`,
+/// and the pool's referral bonus on them into `referral_owed` for the user's
+/// referrer. The bonus comes on top, out of `referral_budget`, and is then
+/// reserved in `rewards_owed`.
+/// Call after `update_pool_rewards` and before changing `amount_staked`.
+pub fn accrue_user_rewards(user_stake: &mut UserStakeAccount, pool: &mut StakePool) -> Result<()> {
+    require!(user_stake.version == USER_STAKE_VERSION, StakingError::AccountNotMigrated);
+    let mut rewards = user_stake.rewards();
+    reward_math::accrue_user_rewards(&mut rewards, &pool.rewards()).map_err(StakingError::from)?;
+    let earned = rewards.pending_rewards - user_stake.pending_rewards;
+    user_stake.set_rewards(rewards);
+
+    if user_stake.referrer.is_some() && pool.referral_bps > 0 {
+        let bonus = ((earned as u128 * pool.referral_bps as u128 / BPS_DENOMINATOR as u128)
+            as u64)
+            .min(pool.referral_budget);
+        pool.referral_budget -= bonus;
+        user_stake.referral_owed = user_stake
+            .referral_owed
+            .checked_add(bonus)
+            .ok_or(StakingError::MathOverflow)?;
+        pool.rewards_owed = pool
+            .rewards_owed
+            .checked_add(bonus)
+            .ok_or(StakingError::MathOverflow)?;
+    }
+    Ok(())
+}
+
+/// Stake-seconds accrued by the user up to the pool's last update.
+pub fn calculate_stake_points(user_stake: &UserStakeAccount, pool: &StakePool) -> Result<u128> {
+    require!(user_stake.version == USER_STAKE_VERSION, StakingError::AccountNotMigrated);
+    Ok(reward_math::calculate_stake_points(&user_stake.rewards(), &pool.rewards())
+        .map_err(StakingError::from)?)
+}
+
+/// Shares `amount` reward tokens pro rata among everyone currently staked.
+/// Nothing is distributed when the pool is empty.
+pub fn distribute_rewards(pool: &mut StakePool, amount: u64) -> Result<()> {
+    let mut rewards = pool.rewards();
+    reward_math::distribute_rewards(&mut rewards, amount).map_err(StakingError::from)?;
+    pool.set_rewards(rewards);
+    Ok(())
+}
+
+/// Drops rewards that were paid out or forfeited from `rewards_owed`.
+pub fn release_owed_rewards(pool: &mut StakePool, amount: u64) {
+    pool.rewards_owed = pool.rewards_owed.saturating_sub(amount);
+}
+
+/// Reward tokens the schedule will still emit from `current_time` until its
+/// last segment starts. The open-ended rate after that is not reserved.
+/// Expects `update_pool_rewards` to have run, so every segment is in the future.
+pub fn scheduled_rewards(pool: &StakePool, current_time: i64) -> Result<u64> {
+    let mut total: u128 = 0;
+    let mut rate = pool.reward_rate;
+    let mut from = current_time;
+    for segment in &pool.reward_schedule {
+        if segment.start_time > from {
+            total += (segment.start_time - from) as u128 * rate as u128;
+            from = segment.start_time;
+        }
+        rate = segment.reward_rate;
+    }
+    u64::try_from(total.div_ceil(PRECISION as u128)).map_err(|_| error!(StakingError::MathOverflow))
+}
+
+/// Recomputes the user's `effective_stake` from `amount_staked` and
+/// `boost_bps`, moving the pool's `total_effective_stake` along. Call after
+/// `accrue_user_rewards` whenever either input changes.
+pub fn update_effective_stake(pool: &mut StakePool, user_stake: &mut UserStakeAccount) -> Result<()> {
+    let effective = u64::try_from(
+        user_stake.amount_staked as u128 * user_stake.boost_bps as u128 / BPS_DENOMINATOR as u128,
+    )
+    .map_err(|_| error!(StakingError::MathOverflow))?;
+    pool.total_effective_stake = pool
+        .total_effective_stake
+        .checked_sub(user_stake.effective_stake)
+        .and_then(|total| total.checked_add(effective))
+        .ok_or(StakingError::MathOverflow)?;
+    user_stake.effective_stake = effective;
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::error::StakingError;
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use anchor_spl::token_2022::{self, spl_token_2022};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+/// Token-2022 mint extensions a pool can hold. Transfer fees are handled by
+/// crediting what the vaults actually receive; extensions that can block, hook
+/// or claw back vault transfers are not supported.
+const SUPPORTED_MINT_EXTENSIONS: [ExtensionType; 9] = [
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::MintCloseAuthority,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::GroupPointer,
+    ExtensionType::TokenGroup,
+    ExtensionType::GroupMemberPointer,
+    ExtensionType::TokenGroupMember,
+];
+
+/// Rejects Token-2022 mints carrying an extension outside `SUPPORTED_MINT_EXTENSIONS`.
+pub fn check_mint_extensions(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let info = mint.to_account_info();
+    if *info.owner != token_2022::ID {
+        return Ok(());
+    }
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    for extension in state.get_extension_types()? {
+        require!(
+            SUPPORTED_MINT_EXTENSIONS.contains(&extension),
+            StakingError::UnsupportedMintExtension
+        );
+    }
+    Ok(())
+}
+
+/// Amount `vault` gained since it held `balance_before`, i.e. a transfer in
+/// net of any transfer fee.
+pub fn received_amount(vault: &mut InterfaceAccount<TokenAccount>, balance_before: u64) -> Result<u64> {
+    vault.reload()?;
+    Ok(vault
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(StakingError::MathOverflow)?)
+}
@@ -0,0 +1,41 @@
+use crate::constants::MAX_VESTING_ENTRIES;
+use crate::error::StakingError;
+use crate::state::{UserStakeAccount, VestingEntry};
+use anchor_lang::prelude::*;
+
+/// Starts vesting `amount` of claimed rewards. Fully withdrawn entries are
+/// dropped first; if every slot is still taken, the newest entry's unreleased
+/// balance is folded in and its schedule restarts.
+pub fn add_vesting_entry(
+    user_stake: &mut UserStakeAccount,
+    amount: u64,
+    current_time: i64,
+    duration: i64,
+) -> Result<()> {
+    user_stake
+        .vesting
+        .retain(|entry| entry.withdrawn < entry.amount);
+    let end_time = current_time
+        .checked_add(duration)
+        .ok_or(StakingError::MathOverflow)?;
+    if user_stake.vesting.len() < MAX_VESTING_ENTRIES {
+        user_stake.vesting.push(VestingEntry {
+            amount,
+            withdrawn: 0,
+            start_time: current_time,
+            end_time,
+        });
+        return Ok(());
+    }
+    let newest = user_stake
+        .vesting
+        .last_mut()
+        .ok_or(StakingError::MathOverflow)?;
+    newest.amount = (newest.amount - newest.withdrawn)
+        .checked_add(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    newest.withdrawn = 0;
+    newest.start_time = current_time;
+    newest.end_time = end_time;
+    Ok(())
+}
@@ -0,0 +1,258 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction, system_program};
+use anchor_spl::token::spl_token;
+use staking::state::{BoostConfig, BoostSource};
+
+use crate::fixtures::{collection_nft, staking_ix, Staker, TestPool};
+
+/// A pool with a 2x boost from `source`, and two stakers with 1_000 each.
+fn boosted_pool(source: BoostSource) -> (TestPool, Staker, Staker) {
+    let mut pool = TestPool::new(|pool| {
+        pool.boost = Some(BoostConfig {
+            source,
+            multiplier_bps: 20_000,
+        })
+    });
+    let [alice, bob] = [(); 2].map(|_| pool.staker(1_000));
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    pool.ledger.process(pool.stake(&bob, 1_000)).unwrap();
+    (pool, alice, bob)
+}
+
+fn boost_vault(pool: &TestPool, boost_mint: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"boost_vault",
+            pool.stake_pool.as_ref(),
+            boost_mint.as_ref(),
+        ],
+        &staking::ID,
+    )
+    .0
+}
+
+fn lock(
+    pool: &TestPool,
+    staker: &Staker,
+    user_boost_account: Pubkey,
+    boost_mint: Pubkey,
+    amount: u64,
+) -> Instruction {
+    staking_ix(
+        staking::accounts::LockBoost {
+            user: staker.wallet,
+            stake_pool: pool.stake_pool,
+            user_stake_account: pool.user_stake(staker.wallet),
+            user_boost_account,
+            boost_vault: boost_vault(pool, boost_mint),
+            boost_mint,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        staking::instruction::LockBoost { amount },
+    )
+}
+
+fn unlock(
+    pool: &TestPool,
+    staker: &Staker,
+    user_boost_account: Pubkey,
+    boost_mint: Pubkey,
+    amount: u64,
+) -> Instruction {
+    staking_ix(
+        staking::accounts::UnlockBoost {
+            user: staker.wallet,
+            stake_pool: pool.stake_pool,
+            user_stake_account: pool.user_stake(staker.wallet),
+            user_boost_account,
+            boost_vault: boost_vault(pool, boost_mint),
+            boost_mint,
+            token_program: spl_token::ID,
+        },
+        staking::instruction::UnlockBoost { amount },
+    )
+}
+
+#[test]
+fn locked_boost_tokens_boost_one_staker_at_a_time() {
+    let boost_mint = Pubkey::new_unique();
+    let (mut pool, alice, bob) = boosted_pool(BoostSource::Token {
+        mint: boost_mint,
+        min_balance: 100,
+    });
+    pool.ledger.mint(boost_mint, Pubkey::new_unique(), 100);
+    let [alice_boost, bob_boost] = [Pubkey::new_unique(), Pubkey::new_unique()];
+    pool.ledger
+        .token_account(alice_boost, boost_mint, alice.wallet, 100);
+    pool.ledger
+        .token_account(bob_boost, boost_mint, bob.wallet, 0);
+
+    // Only the boost mint can be locked, and the boost needs min_balance
+    assert!(pool
+        .ledger
+        .process(lock(&pool, &alice, alice.tokens, pool.mint, 100))
+        .is_err());
+    pool.ledger
+        .process(lock(&pool, &alice, alice_boost, boost_mint, 50))
+        .unwrap();
+    assert_eq!(pool.position(&alice).effective_stake, 1_000);
+    pool.ledger
+        .process(lock(&pool, &alice, alice_boost, boost_mint, 50))
+        .unwrap();
+    assert_eq!(pool.position(&alice).effective_stake, 2_000);
+    assert_eq!(pool.pool().total_effective_stake, 3_000);
+
+    // The pool holds the tokens, so they cannot move on to boost Bob too
+    assert!(pool
+        .ledger
+        .process(
+            spl_token::instruction::transfer(
+                &spl_token::ID,
+                &alice_boost,
+                &bob_boost,
+                &alice.wallet,
+                &[],
+                100,
+            )
+            .unwrap(),
+        )
+        .is_err());
+    assert!(pool
+        .ledger
+        .process(lock(&pool, &bob, bob_boost, boost_mint, 100))
+        .is_err());
+    assert_eq!(pool.pool().boost_locked, 100);
+
+    // Nor can the boost move to another mint while they are locked
+    let set_boost = |boost| {
+        staking_ix(
+            staking::accounts::SetBoost {
+                authority: pool.authority,
+                stake_pool: pool.stake_pool,
+            },
+            staking::instruction::SetBoost { boost },
+        )
+    };
+    let other_boost = BoostConfig {
+        source: BoostSource::Token {
+            mint: Pubkey::new_unique(),
+            min_balance: 100,
+        },
+        multiplier_bps: 20_000,
+    };
+    assert!(pool.ledger.process(set_boost(Some(other_boost))).is_err());
+    assert!(pool.ledger.process(set_boost(None)).is_err());
+
+    // Unlocked and handed to Bob, the tokens boost him instead
+    pool.ledger
+        .process(unlock(&pool, &alice, alice_boost, boost_mint, 100))
+        .unwrap();
+    assert_eq!(pool.position(&alice).effective_stake, 1_000);
+    pool.ledger
+        .process(
+            spl_token::instruction::transfer(
+                &spl_token::ID,
+                &alice_boost,
+                &bob_boost,
+                &alice.wallet,
+                &[],
+                100,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    pool.ledger
+        .process(lock(&pool, &bob, bob_boost, boost_mint, 100))
+        .unwrap();
+    assert_eq!(pool.position(&bob).effective_stake, 2_000);
+    assert_eq!(pool.pool().total_effective_stake, 3_000);
+    assert!(pool
+        .ledger
+        .process(unlock(&pool, &alice, alice_boost, boost_mint, 1))
+        .is_err());
+}
+
+#[test]
+fn collection_nft_boosts_one_staker_at_a_time() {
+    let collection = Pubkey::new_unique();
+    let (mut pool, alice, bob) = boosted_pool(BoostSource::Collection { collection });
+    let (nft, alice_nft_account) = collection_nft(&mut pool.ledger, collection, alice.wallet);
+    let (claim, _) = Pubkey::find_program_address(
+        &[b"boost_claim", pool.stake_pool.as_ref(), nft.as_ref()],
+        &staking::ID,
+    );
+
+    let stake_pool = pool.stake_pool;
+    let positions = [alice, bob].map(|staker| (staker.wallet, pool.user_stake(staker.wallet)));
+    let refresh = |caller, staker: &Staker, holding, claims: bool, releases: bool| {
+        let (_, user_stake_account) = positions
+            .into_iter()
+            .find(|(wallet, _)| *wallet == staker.wallet)
+            .unwrap();
+        staking_ix(
+            staking::accounts::RefreshBoost {
+                caller,
+                stake_pool,
+                user_stake_account,
+                user: staker.wallet,
+                boost_token_account: Some(holding),
+                boost_mint: Some(nft),
+                boost_nft_metadata: None,
+                claimed_holding: claims.then_some(nft),
+                boost_claim: claims.then_some(claim),
+                previous_boost_claim: releases.then_some(claim),
+                system_program: system_program::ID,
+            },
+            staking::instruction::RefreshBoost {},
+        )
+    };
+    // A boost needs the NFT's claim
+    assert!(pool
+        .ledger
+        .process(refresh(
+            alice.wallet,
+            &alice,
+            alice_nft_account,
+            false,
+            false
+        ))
+        .is_err());
+    pool.ledger
+        .process(refresh(
+            alice.wallet,
+            &alice,
+            alice_nft_account,
+            true,
+            false,
+        ))
+        .unwrap();
+    assert_eq!(pool.position(&alice).effective_stake, 2_000);
+    assert_eq!(pool.pool().total_effective_stake, 3_000);
+
+    // Moved to a fresh account of Bob's, the NFT is still claimed
+    pool.ledger
+        .token_account(alice_nft_account, nft, alice.wallet, 0);
+    let bob_nft_account = Pubkey::new_unique();
+    pool.ledger
+        .token_account(bob_nft_account, nft, bob.wallet, 1);
+    assert!(pool
+        .ledger
+        .process(refresh(bob.wallet, &bob, bob_nft_account, true, false))
+        .is_err());
+    assert!(pool
+        .ledger
+        .process(refresh(bob.wallet, &bob, bob_nft_account, false, false))
+        .is_err());
+
+    // Once Alice's lapsed boost is refreshed away, it can move
+    pool.ledger
+        .process(refresh(bob.wallet, &alice, alice_nft_account, false, true))
+        .unwrap();
+    assert_eq!(pool.position(&alice).effective_stake, 1_000);
+    assert!(pool.position(&alice).boost_claim.is_none());
+    pool.ledger
+        .process(refresh(bob.wallet, &bob, bob_nft_account, true, false))
+        .unwrap();
+    assert_eq!(pool.position(&bob).effective_stake, 2_000);
+    assert_eq!(pool.pool().total_effective_stake, 3_000);
+}
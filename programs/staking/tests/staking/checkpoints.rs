@@ -0,0 +1,54 @@
+use crate::fixtures::TestPool;
+
+/// `MAX_STAKE_CHECKPOINTS` in the program.
+const MAX_STAKE_CHECKPOINTS: u64 = 32;
+
+#[test]
+fn balances_are_read_back_by_slot() {
+    let mut pool = TestPool::new(|_| {});
+    let alice = pool.staker(1_000);
+    let before = pool.ledger.slot();
+
+    pool.ledger.advance_slots(10);
+    pool.ledger.process(pool.stake(&alice, 400)).unwrap();
+    let staked = pool.ledger.slot();
+    pool.ledger.advance_slots(10);
+    pool.ledger.process(pool.unstake(&alice, 100)).unwrap();
+
+    let history = pool.position(&alice).stake_history;
+    assert_eq!(history.balance_at(before).unwrap(), 0);
+    assert_eq!(history.balance_at(staked).unwrap(), 400);
+    assert_eq!(history.balance_at(staked + 9).unwrap(), 400);
+    assert_eq!(history.balance_at(pool.ledger.slot()).unwrap(), 300);
+    let total = pool.pool().stake_history;
+    assert_eq!(total.balance_at(staked).unwrap(), 400);
+    assert_eq!(total.balance_at(pool.ledger.slot()).unwrap(), 300);
+}
+
+#[test]
+fn slots_older_than_the_retained_history_are_unavailable() {
+    let mut pool = TestPool::new(|_| {});
+    let alice = pool.staker(1_000);
+    let first = pool.ledger.slot();
+    for _ in 0..=MAX_STAKE_CHECKPOINTS {
+        pool.ledger.process(pool.stake(&alice, 1)).unwrap();
+        pool.ledger.advance_slots(1);
+    }
+
+    // The first checkpoint was overwritten, the second is the oldest left
+    let history = pool.position(&alice).stake_history;
+    assert!(history.balance_at(first - 1).is_err());
+    assert!(history.balance_at(first).is_err());
+    assert_eq!(history.balance_at(first + 1).unwrap(), 2);
+    assert_eq!(
+        history.balance_at(pool.ledger.slot()).unwrap(),
+        MAX_STAKE_CHECKPOINTS + 1
+    );
+    assert!(pool
+        .ledger
+        .process(pool.get_stake_at(&alice, first))
+        .is_err());
+    pool.ledger
+        .process(pool.get_stake_at(&alice, first + 1))
+        .unwrap();
+}
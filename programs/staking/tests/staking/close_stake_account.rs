@@ -0,0 +1,43 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction};
+
+use crate::fixtures::{staking_ix, Staker, TestPool};
+
+fn close(pool: &TestPool, staker: &Staker) -> Instruction {
+    staking_ix(
+        staking::accounts::CloseStakeAccount {
+            user: staker.wallet,
+            stake_pool: pool.stake_pool,
+            user_stake_account: pool.user_stake(staker.wallet),
+        },
+        staking::instruction::CloseStakeAccount {},
+    )
+}
+
+#[test]
+fn emptied_stake_accounts_close_and_refund_their_rent() {
+    let mut pool = TestPool::new(|_| {});
+    let alice = pool.staker(1_000);
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    pool.ledger.advance_clock(10);
+
+    // Neither stake nor unclaimed rewards can be left behind
+    assert!(pool.ledger.process(close(&pool, &alice)).is_err());
+    pool.ledger.process(pool.unstake(&alice, 1_000)).unwrap();
+    assert_eq!(pool.position(&alice).pending_rewards, 10);
+    assert!(pool.ledger.process(close(&pool, &alice)).is_err());
+    pool.ledger.process(pool.claim(&alice)).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 1_010);
+
+    let user_stake = pool.user_stake(alice.wallet);
+    let (wallet, rent) = (
+        pool.ledger.lamports(&alice.wallet),
+        pool.ledger.lamports(&user_stake),
+    );
+    pool.ledger.process(close(&pool, &alice)).unwrap();
+    assert_eq!(pool.ledger.lamports(&user_stake), 0);
+    assert_eq!(pool.ledger.lamports(&alice.wallet), wallet + rent);
+
+    // A later deposit opens the account afresh
+    pool.ledger.process(pool.stake(&alice, 500)).unwrap();
+    assert_eq!(pool.position(&alice).amount_staked, 500);
+}
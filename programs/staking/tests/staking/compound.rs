@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::fixtures::{staking_ix, TestPool};
+
+#[test]
+fn keepers_compound_only_for_stakers_who_opt_in() {
+    let mut pool = TestPool::new(|_| {});
+    let alice = pool.staker(1_000);
+    let keeper = Pubkey::new_unique();
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    let set_auto_compound = |user, enabled| {
+        staking_ix(
+            staking::accounts::SetAutoCompound {
+                user,
+                stake_pool: pool.stake_pool,
+                user_stake_account: pool.user_stake(alice.wallet),
+            },
+            staking::instruction::SetAutoCompound { enabled },
+        )
+    };
+    let [by_keeper, opt_in, opt_out] = [
+        set_auto_compound(keeper, true),
+        set_auto_compound(alice.wallet, true),
+        set_auto_compound(alice.wallet, false),
+    ];
+
+    pool.ledger.advance_clock(100);
+    assert!(pool.ledger.process(pool.compound(keeper, &alice)).is_err());
+    assert!(pool.ledger.process(by_keeper).is_err());
+    pool.ledger.process(opt_in).unwrap();
+    pool.ledger.process(pool.compound(keeper, &alice)).unwrap();
+    assert_eq!(pool.position(&alice).amount_staked, 1_100);
+    assert_eq!(pool.pool().total_staked, 1_100);
+
+    // Opting out stops the keeper but not Alice herself
+    pool.ledger.process(opt_out).unwrap();
+    pool.ledger.advance_clock(110);
+    assert!(pool.ledger.process(pool.compound(keeper, &alice)).is_err());
+    pool.ledger
+        .process(pool.compound(alice.wallet, &alice))
+        .unwrap();
+    assert_eq!(pool.position(&alice).amount_staked, 1_210);
+}
@@ -0,0 +1,105 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction};
+use anchor_spl::token::spl_token;
+use staking::state::EarlyExitConfig;
+
+use crate::fixtures::{staking_ix, Staker, TestPool};
+
+/// A pool locking stake for 100 seconds, with no rewards of its own so that
+/// only penalties are paid out.
+fn locked_pool(early_exit: EarlyExitConfig) -> TestPool {
+    TestPool::new(|pool| {
+        pool.reward_rate = 0;
+        pool.min_stake_duration = 100;
+        pool.early_exit = early_exit;
+    })
+}
+
+fn unstake_early(pool: &TestPool, staker: &Staker, treasury: Pubkey, amount: u64) -> Instruction {
+    staking_ix(
+        staking::accounts::Unstake {
+            user: staker.wallet,
+            user_stake_account: pool.user_stake(staker.wallet),
+            user_token_account: staker.tokens,
+            stake_pool: pool.stake_pool,
+            pool_stake_vault: pool.pool_stake_vault,
+            pool_reward_vault: None,
+            penalty_treasury: Some(treasury),
+            stake_mint: pool.mint,
+            token_program: spl_token::ID,
+        },
+        staking::instruction::Unstake { amount },
+    )
+}
+
+#[test]
+fn early_exits_pay_the_penalty_to_the_treasury() {
+    let treasury = Pubkey::new_unique();
+    let mut pool = locked_pool(EarlyExitConfig {
+        penalty_bps: 1_000,
+        linear_decay: false,
+        treasury: Some(treasury),
+    });
+    pool.ledger
+        .token_account(treasury, pool.mint, Pubkey::new_unique(), 0);
+    let alice = pool.staker(1_000);
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+
+    // The penalty needs the configured treasury
+    pool.ledger.advance_clock(50);
+    assert!(pool.ledger.process(pool.unstake(&alice, 500)).is_err());
+    let elsewhere = pool.staker(0).tokens;
+    assert!(pool
+        .ledger
+        .process(unstake_early(&pool, &alice, elsewhere, 500))
+        .is_err());
+    pool.ledger
+        .process(unstake_early(&pool, &alice, treasury, 500))
+        .unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 450);
+    assert_eq!(pool.ledger.token_balance(&treasury), 50);
+    assert_eq!(pool.position(&alice).amount_staked, 500);
+
+    // Once the lock runs out the rest comes back whole
+    pool.ledger.advance_clock(50);
+    pool.ledger.process(pool.unstake(&alice, 500)).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 950);
+    assert_eq!(pool.ledger.token_balance(&treasury), 50);
+}
+
+#[test]
+fn decaying_penalties_are_shared_by_the_stakers_who_stay() {
+    let mut pool = locked_pool(EarlyExitConfig {
+        penalty_bps: 1_000,
+        linear_decay: true,
+        treasury: None,
+    });
+    let [alice, bob] = [(); 2].map(|_| pool.staker(1_000));
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    pool.ledger.process(pool.stake(&bob, 1_000)).unwrap();
+
+    // Halfway through the lock, half of the 10% penalty is left
+    pool.ledger.advance_clock(50);
+    pool.ledger.process(pool.unstake(&alice, 1_000)).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 950);
+    assert_eq!(
+        pool.ledger.token_balance(&pool.pool_reward_vault),
+        1_000_050
+    );
+    pool.ledger
+        .process(pool.compound(bob.wallet, &bob))
+        .unwrap();
+    assert_eq!(pool.position(&bob).amount_staked, 1_050);
+}
+
+#[test]
+fn pools_without_a_penalty_keep_the_hard_lock() {
+    let mut pool = locked_pool(EarlyExitConfig::default());
+    let alice = pool.staker(1_000);
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+
+    pool.ledger.advance_clock(99);
+    assert!(pool.ledger.process(pool.unstake(&alice, 1_000)).is_err());
+    pool.ledger.advance_clock(1);
+    pool.ledger.process(pool.unstake(&alice, 1_000)).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 1_000);
+}
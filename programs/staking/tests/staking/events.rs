@@ -0,0 +1,53 @@
+use staking::events::{Staked, Unstaked};
+
+use crate::fixtures::TestPool;
+
+/// The one event that the last instruction emitted.
+fn only<T>(events: Vec<T>) -> T {
+    assert_eq!(events.len(), 1);
+    events.into_iter().next().unwrap()
+}
+
+#[test]
+fn stakes_and_unstakes_are_announced() {
+    let mut pool = TestPool::new(|_| {});
+    let [alice, funder] = [(); 2].map(|_| pool.staker(1_000));
+
+    pool.ledger.process(pool.stake(&alice, 600)).unwrap();
+    let staked = only(pool.ledger.events::<Staked>());
+    assert_eq!(
+        (staked.pool, staked.user, staked.funder),
+        (pool.stake_pool, alice.wallet, alice.wallet)
+    );
+    assert_eq!((staked.amount, staked.pool_total_staked), (600, 600));
+
+    pool.ledger
+        .process(pool.stake_for(&funder, alice.wallet, 400, None))
+        .unwrap();
+    let staked = only(pool.ledger.events::<Staked>());
+    assert_eq!((staked.user, staked.funder), (alice.wallet, funder.wallet));
+    assert_eq!((staked.amount, staked.user_total_staked), (400, 1_000));
+
+    pool.ledger.process(pool.unstake(&alice, 250)).unwrap();
+    assert!(pool.ledger.events::<Staked>().is_empty());
+    let unstaked = only(pool.ledger.events::<Unstaked>());
+    assert_eq!(
+        (
+            unstaked.amount,
+            unstaked.penalty,
+            unstaked.pool_total_staked
+        ),
+        (250, 0, 750)
+    );
+}
+
+#[test]
+fn rejected_stakes_announce_nothing() {
+    let mut pool = TestPool::new(|_| {});
+    let alice = pool.staker(1_000);
+
+    assert!(pool.ledger.process(pool.stake(&alice, 1_001)).is_err());
+    assert!(pool.ledger.events::<Staked>().is_empty());
+    assert!(pool.ledger.process(pool.stake(&alice, 0)).is_err());
+    assert!(pool.ledger.events::<Staked>().is_empty());
+}
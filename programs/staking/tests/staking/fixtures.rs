@@ -0,0 +1,291 @@
+use anchor_lang::{
+    prelude::*, solana_program::instruction::Instruction, system_program, InstructionData, Space,
+    ToAccountMetas,
+};
+use anchor_spl::token::spl_token::{self, solana_program::program_pack::Pack};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut},
+};
+use native_runtime::Ledger;
+use spl_token_group_interface::state::TokenGroupMember;
+use staking::state::{StakePool, UserStakeAccount};
+
+/// A ledger with the staking program deployed.
+pub fn new_ledger() -> Ledger {
+    let mut ledger = Ledger::new();
+    ledger.add_program(staking::ID, staking::entry);
+    ledger
+}
+
+pub fn staking_ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction::new_with_bytes(staking::ID, &data.data(), accounts.to_account_metas(None))
+}
+
+/// A Token-2022 NFT in `collection`, held by `owner`. Returns its mint and
+/// token account.
+pub fn collection_nft(ledger: &mut Ledger, collection: Pubkey, owner: Pubkey) -> (Pubkey, Pubkey) {
+    use spl_token_2022::state::Mint;
+    let [mint, token_account] = [(); 2].map(|_| Pubkey::new_unique());
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TokenGroupMember])
+            .unwrap();
+    let mut data = vec![0; space];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    *state.init_extension::<TokenGroupMember>(true).unwrap() =
+        TokenGroupMember::new(&mint, &collection, 1);
+    state.base = Mint {
+        supply: 1,
+        decimals: 0,
+        is_initialized: true,
+        ..Mint::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    ledger.set(mint, spl_token_2022::ID, 1_000_000_000, data, false);
+    ledger.token_account(token_account, mint, owner, 1);
+    (mint, token_account)
+}
+
+/// A Metaplex NFT under SPL Token, with `collection` in its metadata and
+/// held by `owner`. Returns its mint, metadata and token account.
+pub fn metaplex_nft(
+    ledger: &mut Ledger,
+    collection: Pubkey,
+    verified: bool,
+    owner: Pubkey,
+) -> (Pubkey, Pubkey, Pubkey) {
+    use spl_token::state::Mint;
+    let [mint, token_account] = [(); 2].map(|_| Pubkey::new_unique());
+    let mut data = vec![0; Mint::LEN];
+    let nft = Mint {
+        supply: 1,
+        decimals: 0,
+        is_initialized: true,
+        ..Mint::default()
+    };
+    Mint::pack(nft, &mut data).unwrap();
+    ledger.set(mint, spl_token::ID, 1_000_000_000, data, false);
+    ledger.token_account(token_account, mint, owner, 1);
+
+    let metadata_program = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+    let (metadata, _) = Pubkey::find_program_address(
+        &[b"metadata", metadata_program.as_ref(), mint.as_ref()],
+        &metadata_program,
+    );
+    // MetadataV1 with empty strings, no creators and a token standard of NonFungible
+    let mut data = [&[4][..], &[0; 32], mint.as_ref()].concat();
+    data.extend([0; 12]);
+    data.extend([0, 0, 0, 0, 1, 0, 1, 0]);
+    data.extend([1, verified as u8]);
+    data.extend(collection.as_ref());
+    data.resize(679, 0);
+    ledger.set(metadata, metadata_program, 1_000_000_000, data, false);
+    (mint, metadata, token_account)
+}
+
+/// A pool paying rewards in its own stake mint, with a funded reward vault.
+pub struct TestPool {
+    pub ledger: Ledger,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub stake_pool: Pubkey,
+    pub pool_stake_vault: Pubkey,
+    pub pool_reward_vault: Pubkey,
+}
+
+/// A funded wallet with a stake token account.
+#[derive(Clone, Copy)]
+pub struct Staker {
+    pub wallet: Pubkey,
+    pub tokens: Pubkey,
+}
+
+impl TestPool {
+    /// Pays 1 reward token a second out of 1_000_000, after `configure` has
+    /// set up the pool. `configure` may swap the stake mint, for NFT pools.
+    pub fn new(configure: impl FnOnce(&mut StakePool)) -> Self {
+        let mut ledger = new_ledger();
+        let authority = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut pool = StakePool {
+            authority,
+            stake_mint: mint,
+            reward_mint: mint,
+            reward_rate: 1_000_000_000,
+            last_updated: ledger.now(),
+            version: 1,
+            ..StakePool::default()
+        };
+        configure(&mut pool);
+        let (stake_pool, bump) = Pubkey::find_program_address(
+            &[
+                b"stake_pool",
+                pool.stake_mint.as_ref(),
+                pool.reward_mint.as_ref(),
+            ],
+            &staking::ID,
+        );
+        pool.bump = bump;
+        let (pool_stake_vault, _) = Pubkey::find_program_address(
+            &[
+                b"stake_vault",
+                stake_pool.as_ref(),
+                pool.stake_mint.as_ref(),
+            ],
+            &staking::ID,
+        );
+        let (pool_reward_vault, _) = Pubkey::find_program_address(
+            &[
+                b"reward_vault",
+                stake_pool.as_ref(),
+                pool.reward_mint.as_ref(),
+            ],
+            &staking::ID,
+        );
+        ledger.set_anchor(stake_pool, staking::ID, &pool, 8 + StakePool::INIT_SPACE);
+        ledger.fund(authority);
+        ledger.mint(mint, Pubkey::new_unique(), 10_000_000);
+        ledger.token_account(pool_stake_vault, pool.stake_mint, stake_pool, 0);
+        ledger.token_account(pool_reward_vault, pool.reward_mint, stake_pool, 1_000_000);
+        Self {
+            ledger,
+            authority,
+            mint,
+            stake_pool,
+            pool_stake_vault,
+            pool_reward_vault,
+        }
+    }
+
+    pub fn staker(&mut self, tokens: u64) -> Staker {
+        let [wallet, token_account] = [(); 2].map(|_| Pubkey::new_unique());
+        self.ledger.fund(wallet);
+        self.ledger
+            .token_account(token_account, self.mint, wallet, tokens);
+        Staker {
+            wallet,
+            tokens: token_account,
+        }
+    }
+
+    pub fn user_stake(&self, wallet: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"user_stake", self.stake_pool.as_ref(), wallet.as_ref()],
+            &staking::ID,
+        )
+        .0
+    }
+
+    pub fn pool(&self) -> StakePool {
+        self.ledger.get(&self.stake_pool)
+    }
+
+    pub fn position(&self, staker: &Staker) -> UserStakeAccount {
+        self.ledger.get(&self.user_stake(staker.wallet))
+    }
+
+    pub fn stake(&self, staker: &Staker, amount: u64) -> Instruction {
+        staking_ix(
+            staking::accounts::Stake {
+                user: staker.wallet,
+                stake_pool: self.stake_pool,
+                user_stake_account: self.user_stake(staker.wallet),
+                user_token_account: staker.tokens,
+                pool_stake_vault: self.pool_stake_vault,
+                stake_mint: self.mint,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            staking::instruction::Stake {
+                amount,
+                referrer: None,
+            },
+        )
+    }
+
+    pub fn unstake(&self, staker: &Staker, amount: u64) -> Instruction {
+        staking_ix(
+            staking::accounts::Unstake {
+                user: staker.wallet,
+                user_stake_account: self.user_stake(staker.wallet),
+                user_token_account: staker.tokens,
+                stake_pool: self.stake_pool,
+                pool_stake_vault: self.pool_stake_vault,
+                pool_reward_vault: Some(self.pool_reward_vault),
+                penalty_treasury: None,
+                stake_mint: self.mint,
+                token_program: spl_token::ID,
+            },
+            staking::instruction::Unstake { amount },
+        )
+    }
+
+    /// `funder` stakes into `beneficiary`'s account, locked until `lock_until`.
+    pub fn stake_for(
+        &self,
+        funder: &Staker,
+        beneficiary: Pubkey,
+        amount: u64,
+        lock_until: Option<i64>,
+    ) -> Instruction {
+        staking_ix(
+            staking::accounts::StakeFor {
+                funder: funder.wallet,
+                stake_pool: self.stake_pool,
+                user_stake_account: self.user_stake(beneficiary),
+                funder_token_account: funder.tokens,
+                pool_stake_vault: self.pool_stake_vault,
+                stake_mint: self.mint,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            staking::instruction::StakeFor {
+                beneficiary,
+                amount,
+                lock_until,
+            },
+        )
+    }
+
+    pub fn get_stake_at(&self, staker: &Staker, slot: u64) -> Instruction {
+        staking_ix(
+            staking::accounts::GetStakeAt {
+                stake_pool: self.stake_pool,
+                user_stake_account: self.user_stake(staker.wallet),
+            },
+            staking::instruction::GetStakeAt { slot },
+        )
+    }
+
+    pub fn claim(&self, staker: &Staker) -> Instruction {
+        staking_ix(
+            staking::accounts::ClaimRewards {
+                user: staker.wallet,
+                stake_pool: self.stake_pool,
+                user_stake_account: self.user_stake(staker.wallet),
+                user_reward_account: staker.tokens,
+                pool_reward_vault: self.pool_reward_vault,
+                reward_mint: self.mint,
+                token_program: spl_token::ID,
+            },
+            staking::instruction::ClaimRewards {},
+        )
+    }
+
+    /// `caller` compounds `staker`'s rewards.
+    pub fn compound(&self, caller: Pubkey, staker: &Staker) -> Instruction {
+        staking_ix(
+            staking::accounts::Compound {
+                caller,
+                stake_pool: self.stake_pool,
+                user_stake_account: self.user_stake(staker.wallet),
+                pool_stake_vault: self.pool_stake_vault,
+                pool_reward_vault: self.pool_reward_vault,
+                stake_mint: self.mint,
+                token_program: spl_token::ID,
+            },
+            staking::instruction::Compound {},
+        )
+    }
+}
@@ -0,0 +1,79 @@
+use staking::state::PoolLimits;
+
+use crate::fixtures::TestPool;
+
+fn capped_pool() -> TestPool {
+    TestPool::new(|pool| {
+        pool.limits = PoolLimits {
+            max_total_staked: Some(1_500),
+            max_per_user: Some(1_000),
+            min_stake_amount: 100,
+        }
+    })
+}
+
+#[test]
+fn deposits_are_held_to_the_pool_limits() {
+    let mut pool = capped_pool();
+    let alice = pool.staker(2_000);
+    let bob = pool.staker(2_000);
+
+    assert!(pool.ledger.process(pool.stake(&alice, 99)).is_err());
+    assert!(pool.ledger.process(pool.stake(&alice, 1_001)).is_err());
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    assert!(pool.ledger.process(pool.stake(&alice, 100)).is_err());
+
+    // Only 500 is left under the pool cap
+    assert!(pool.ledger.process(pool.stake(&bob, 600)).is_err());
+    pool.ledger.process(pool.stake(&bob, 500)).unwrap();
+    assert_eq!(pool.pool().total_staked, 1_500);
+}
+
+#[test]
+fn compounding_goes_past_the_deposit_limits() {
+    let mut pool = capped_pool();
+    let alice = pool.staker(1_000);
+    let bob = pool.staker(500);
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    pool.ledger.process(pool.stake(&bob, 500)).unwrap();
+
+    // Both caps are reached, and 300 of rewards accrue over 300 seconds
+    pool.ledger.advance_clock(300);
+    pool.ledger
+        .process(pool.compound(alice.wallet, &alice))
+        .unwrap();
+    assert_eq!(pool.position(&alice).amount_staked, 1_200);
+    assert_eq!(pool.pool().total_staked, 1_700);
+
+    // New deposits stay capped
+    let carol = pool.staker(100);
+    assert!(pool.ledger.process(pool.stake(&carol, 100)).is_err());
+}
+
+#[test]
+fn withdrawals_free_capacity_and_funders_meet_the_same_limits() {
+    let mut pool = capped_pool();
+    let [alice, bob, funder] = [(); 3].map(|_| pool.staker(1_000));
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    pool.ledger.process(pool.stake(&bob, 500)).unwrap();
+
+    // Staking for Bob counts against his cap and the pool's, and the minimum
+    assert!(pool
+        .ledger
+        .process(pool.stake_for(&funder, bob.wallet, 100, None))
+        .is_err());
+    pool.ledger.process(pool.unstake(&alice, 400)).unwrap();
+    assert!(pool
+        .ledger
+        .process(pool.stake_for(&funder, bob.wallet, 99, None))
+        .is_err());
+    assert!(pool
+        .ledger
+        .process(pool.stake_for(&funder, bob.wallet, 501, None))
+        .is_err());
+    pool.ledger
+        .process(pool.stake_for(&funder, bob.wallet, 400, None))
+        .unwrap();
+    assert_eq!(pool.position(&bob).amount_staked, 900);
+    assert_eq!(pool.pool().total_staked, 1_500);
+}
@@ -0,0 +1,64 @@
+use crate::fixtures::TestPool;
+
+/// `MAX_STAKE_LOTS` in the program.
+const MAX_STAKE_LOTS: usize = 10;
+
+fn locked_pool() -> TestPool {
+    TestPool::new(|pool| pool.min_stake_duration = 100)
+}
+
+#[test]
+fn each_deposit_is_locked_from_its_own_start() {
+    let mut pool = locked_pool();
+    let alice = pool.staker(1_000);
+    pool.ledger.process(pool.stake(&alice, 600)).unwrap();
+    pool.ledger.advance_clock(50);
+    pool.ledger.process(pool.stake(&alice, 400)).unwrap();
+    let start = pool.ledger.now();
+    let lots = pool.position(&alice).lots;
+    assert_eq!(lots.len(), 2);
+    assert_eq!((lots[1].amount, lots[1].start_time), (400, start));
+
+    // The first deposit unlocks on its own, and is withdrawn first
+    pool.ledger.advance_clock(50);
+    assert!(pool.ledger.process(pool.unstake(&alice, 601)).is_err());
+    pool.ledger.process(pool.unstake(&alice, 500)).unwrap();
+    let lots = pool.position(&alice).lots;
+    assert_eq!(
+        lots.iter().map(|lot| lot.amount).collect::<Vec<_>>(),
+        [100, 400]
+    );
+    pool.ledger.process(pool.unstake(&alice, 100)).unwrap();
+    assert!(pool.ledger.process(pool.unstake(&alice, 400)).is_err());
+
+    pool.ledger.advance_clock(50);
+    pool.ledger.process(pool.unstake(&alice, 400)).unwrap();
+    assert!(pool.position(&alice).lots.is_empty());
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 1_000);
+}
+
+#[test]
+fn deposits_past_the_last_lot_merge_without_shortening_its_lock() {
+    let mut pool = locked_pool();
+    let alice = pool.staker(2_000);
+    for _ in 0..MAX_STAKE_LOTS {
+        pool.ledger.process(pool.stake(&alice, 100)).unwrap();
+        pool.ledger.advance_clock(10);
+    }
+    let newest = pool.position(&alice).lots[MAX_STAKE_LOTS - 1];
+
+    // 300 more, 10 seconds after the newest lot's 100, move its start by 7.5s
+    pool.ledger.process(pool.stake(&alice, 300)).unwrap();
+    let lots = pool.position(&alice).lots;
+    assert_eq!(lots.len(), MAX_STAKE_LOTS);
+    let merged = lots[MAX_STAKE_LOTS - 1];
+    assert_eq!(merged.amount, 400);
+    assert_eq!(merged.start_time, newest.start_time + 8);
+
+    // The merged lot unlocks only when its weighted start allows
+    pool.ledger.advance_clock(97);
+    assert!(pool.ledger.process(pool.unstake(&alice, 1_300)).is_err());
+    pool.ledger.advance_clock(1);
+    pool.ledger.process(pool.unstake(&alice, 1_300)).unwrap();
+    assert_eq!(pool.position(&alice).amount_staked, 0);
+}
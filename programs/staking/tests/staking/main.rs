@@ -0,0 +1,24 @@
+//! Staking program tests, run natively against an in-memory ledger.
+
+mod boosts;
+mod checkpoints;
+mod close_stake_account;
+mod compound;
+mod early_exit;
+mod events;
+mod fixtures;
+mod limits;
+mod lots;
+mod migration;
+mod nft_pools;
+mod points;
+mod pool_admin;
+mod receipts;
+mod recovery;
+mod referrals;
+mod registry;
+mod reward_schedule;
+mod stake_for;
+mod transfer_fees;
+mod vesting;
+mod views;
@@ -0,0 +1,149 @@
+use anchor_lang::{prelude::*, system_program, Discriminator};
+use anchor_spl::token::spl_token;
+use staking::state::{StakePool, UserStakeAccount};
+
+use crate::fixtures::{new_ledger, staking_ix};
+
+#[test]
+fn migrates_accounts_from_the_original_layout() {
+    let mut ledger = new_ledger();
+
+    let authority = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let stake_mint = Pubkey::new_unique();
+    let reward_mint = Pubkey::new_unique();
+    ledger.fund(authority);
+    ledger.mint(stake_mint, authority, 1_000);
+    ledger.mint(reward_mint, authority, 500);
+
+    // Pool and position as the first release wrote them, with bump last
+    let (stake_pool, pool_bump) = Pubkey::find_program_address(
+        &[b"stake_pool", stake_mint.as_ref(), reward_mint.as_ref()],
+        &staking::ID,
+    );
+    let (pool_reward_vault, _) = Pubkey::find_program_address(
+        &[b"reward_vault", stake_pool.as_ref(), reward_mint.as_ref()],
+        &staking::ID,
+    );
+    let (user_stake, user_bump) = Pubkey::find_program_address(
+        &[b"user_stake", stake_pool.as_ref(), user.as_ref()],
+        &staking::ID,
+    );
+    ledger.token_account(pool_reward_vault, reward_mint, stake_pool, 500);
+    let pool_data = [
+        StakePool::DISCRIMINATOR,
+        authority.as_ref(),
+        stake_mint.as_ref(),
+        reward_mint.as_ref(),
+        &100u64.to_le_bytes(),
+        &7u64.to_le_bytes(),
+        &ledger.now().to_le_bytes(),
+        &60i64.to_le_bytes(),
+        &1_000u64.to_le_bytes(),
+        &[pool_bump],
+    ]
+    .concat();
+    assert_eq!(pool_data.len(), 8 + 137);
+    ledger.set(stake_pool, staking::ID, 1_000_000_000, pool_data, false);
+    let user_data = [
+        UserStakeAccount::DISCRIMINATOR,
+        user.as_ref(),
+        stake_pool.as_ref(),
+        &1_000u64.to_le_bytes(),
+        &5u64.to_le_bytes(),
+        &3u64.to_le_bytes(),
+        &(ledger.now() - 120).to_le_bytes(),
+        &[user_bump],
+    ]
+    .concat();
+    assert_eq!(user_data.len(), 8 + 97);
+    ledger.set(user_stake, staking::ID, 1_000_000_000, user_data, false);
+
+    // Unpaid rewards were never tracked, so the reward vault is needed
+    let migrate_pool = |pool_reward_vault| {
+        staking_ix(
+            staking::accounts::MigratePool {
+                payer: authority,
+                stake_pool,
+                pool_reward_vault,
+                system_program: system_program::ID,
+            },
+            staking::instruction::MigratePool {},
+        )
+    };
+    assert!(ledger.process(migrate_pool(None)).is_err());
+    ledger
+        .process(migrate_pool(Some(pool_reward_vault)))
+        .unwrap();
+
+    let pool = ledger.get::<StakePool>(&stake_pool);
+    assert_eq!(pool.authority, authority);
+    assert_eq!(
+        (pool.stake_mint, pool.reward_mint),
+        (stake_mint, reward_mint)
+    );
+    assert_eq!((pool.reward_rate, pool.reward_per_token_stored), (100, 7));
+    assert_eq!(pool.min_stake_duration, 60);
+    assert_eq!(pool.total_staked, 1_000);
+    assert_eq!(pool.total_effective_stake, 1_000);
+    assert_eq!(pool.early_exit.penalty_bps, 0);
+    assert_eq!(pool.rewards_owed, 500);
+    assert_eq!(pool.bump, pool_bump);
+    assert!(pool.receipt_mint.is_none() && !pool.paused && pool.reward_schedule.is_empty());
+
+    ledger
+        .process(staking_ix(
+            staking::accounts::MigrateUserStake {
+                payer: authority,
+                user_stake_account: user_stake,
+                stake_pool,
+                system_program: system_program::ID,
+            },
+            staking::instruction::MigrateUserStake {},
+        ))
+        .unwrap();
+
+    let position = ledger.get::<UserStakeAccount>(&user_stake);
+    assert_eq!((position.user, position.stake_pool), (user, stake_pool));
+    assert_eq!(position.amount_staked, 1_000);
+    assert_eq!(position.effective_stake, 1_000);
+    assert_eq!(
+        (position.reward_per_token_paid, position.pending_rewards),
+        (5, 3)
+    );
+    assert!(position.vesting.is_empty());
+    assert_eq!(position.bump, user_bump);
+    assert_eq!(position.lots.len(), 1);
+    assert_eq!(position.lots[0].amount, 1_000);
+
+    // The old lock has run out, so the stake can be withdrawn
+    let (pool_stake_vault, _) = Pubkey::find_program_address(
+        &[b"stake_vault", stake_pool.as_ref(), stake_mint.as_ref()],
+        &staking::ID,
+    );
+    let user_tokens = Pubkey::new_unique();
+    ledger.fund(user);
+    ledger.token_account(pool_stake_vault, stake_mint, stake_pool, 1_000);
+    ledger.token_account(user_tokens, stake_mint, user, 0);
+    ledger
+        .process(staking_ix(
+            staking::accounts::Unstake {
+                user,
+                user_stake_account: user_stake,
+                user_token_account: user_tokens,
+                stake_pool,
+                pool_stake_vault,
+                pool_reward_vault: None,
+                penalty_treasury: None,
+                stake_mint,
+                token_program: spl_token::ID,
+            },
+            staking::instruction::Unstake { amount: 400 },
+        ))
+        .unwrap();
+    assert_eq!(
+        ledger.get::<UserStakeAccount>(&user_stake).amount_staked,
+        600
+    );
+    assert_eq!(ledger.get::<StakePool>(&stake_pool).total_staked, 600);
+}
@@ -0,0 +1,127 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction, system_program};
+use anchor_spl::token::spl_token;
+
+use crate::fixtures::{metaplex_nft, staking_ix, TestPool};
+
+/// An NFT pool over `collection`, where NFTs weigh 100 by default.
+fn nft_pool(collection: Pubkey) -> TestPool {
+    TestPool::new(|pool| {
+        pool.stake_mint = collection;
+        pool.nft_pool = true;
+        pool.default_nft_weight = 100;
+    })
+}
+
+fn nft_pda(pool: &TestPool, seed: &[u8], nft: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[seed, pool.stake_pool.as_ref(), nft.as_ref()],
+        &staking::ID,
+    )
+    .0
+}
+
+fn stake_nft(
+    pool: &TestPool,
+    user: Pubkey,
+    nft: Pubkey,
+    metadata: Pubkey,
+    user_nft_account: Pubkey,
+) -> Instruction {
+    staking_ix(
+        staking::accounts::StakeNft {
+            user,
+            stake_pool: pool.stake_pool,
+            user_stake_account: pool.user_stake(user),
+            nft_mint: nft,
+            nft_metadata: Some(metadata),
+            user_nft_account,
+            nft_vault: nft_pda(pool, b"nft_vault", nft),
+            nft_stake_record: nft_pda(pool, b"nft_stake", nft),
+            nft_weight: nft_pda(pool, b"nft_weight", nft),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        staking::instruction::StakeNft {},
+    )
+}
+
+fn unstake_nft(
+    pool: &TestPool,
+    user: Pubkey,
+    nft: Pubkey,
+    user_nft_account: Pubkey,
+) -> Instruction {
+    staking_ix(
+        staking::accounts::UnstakeNft {
+            user,
+            stake_pool: pool.stake_pool,
+            user_stake_account: pool.user_stake(user),
+            nft_stake_record: nft_pda(pool, b"nft_stake", nft),
+            nft_mint: nft,
+            nft_vault: nft_pda(pool, b"nft_vault", nft),
+            user_nft_account,
+            token_program: spl_token::ID,
+        },
+        staking::instruction::UnstakeNft {},
+    )
+}
+
+#[test]
+fn verified_metaplex_nfts_are_staked_one_at_a_time() {
+    let collection = Pubkey::new_unique();
+    let mut pool = nft_pool(collection);
+    let alice = pool.staker(0).wallet;
+    let [first, second] = [(); 2].map(|_| metaplex_nft(&mut pool.ledger, collection, true, alice));
+
+    for (nft, metadata, account) in [first, second] {
+        let stake = stake_nft(&pool, alice, nft, metadata, account);
+        pool.ledger.process(stake).unwrap();
+        assert_eq!(pool.ledger.token_balance(&account), 0);
+    }
+    assert_eq!(
+        pool.ledger
+            .get::<staking::state::UserStakeAccount>(&pool.user_stake(alice))
+            .amount_staked,
+        200
+    );
+    assert_eq!(pool.pool().total_staked, 200);
+
+    // Each NFT comes back on its own
+    let (nft, _, account) = first;
+    pool.ledger
+        .process(unstake_nft(&pool, alice, nft, account))
+        .unwrap();
+    assert_eq!(pool.ledger.token_balance(&account), 1);
+    assert_eq!(pool.pool().total_staked, 100);
+    assert!(pool
+        .ledger
+        .process(unstake_nft(&pool, alice, nft, account))
+        .is_err());
+}
+
+#[test]
+fn nfts_outside_the_verified_collection_are_rejected() {
+    let collection = Pubkey::new_unique();
+    let mut pool = nft_pool(collection);
+    let alice = pool.staker(0).wallet;
+
+    let (nft, metadata, account) = metaplex_nft(&mut pool.ledger, collection, false, alice);
+    assert!(pool
+        .ledger
+        .process(stake_nft(&pool, alice, nft, metadata, account))
+        .is_err());
+    let (nft, metadata, account) =
+        metaplex_nft(&mut pool.ledger, Pubkey::new_unique(), true, alice);
+    assert!(pool
+        .ledger
+        .process(stake_nft(&pool, alice, nft, metadata, account))
+        .is_err());
+
+    // Nor does another NFT's metadata vouch for this one
+    let (_, verified_metadata, _) = metaplex_nft(&mut pool.ledger, collection, true, alice);
+    assert!(pool
+        .ledger
+        .process(stake_nft(&pool, alice, nft, verified_metadata, account))
+        .is_err());
+    assert_eq!(pool.pool().total_staked, 0);
+}
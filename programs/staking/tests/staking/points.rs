@@ -0,0 +1,69 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction};
+use staking::state::RewardSegment;
+
+use crate::fixtures::{staking_ix, Staker, TestPool};
+
+/// A pool with no reward token, only accruing stake points.
+fn points_pool() -> TestPool {
+    TestPool::new(|pool| {
+        pool.reward_mint = Pubkey::default();
+        pool.reward_rate = 0;
+    })
+}
+
+fn get_stake_points(pool: &TestPool, staker: &Staker) -> Instruction {
+    staking_ix(
+        staking::accounts::GetStakePoints {
+            stake_pool: pool.stake_pool,
+            user_stake_account: pool.user_stake(staker.wallet),
+        },
+        staking::instruction::GetStakePoints {},
+    )
+}
+
+/// Stake-seconds `staker` has accrued by now.
+fn stake_points(pool: &mut TestPool, staker: &Staker) -> u128 {
+    pool.ledger.process(get_stake_points(pool, staker)).unwrap();
+    let (_, data) = pool.ledger.return_data().unwrap();
+    u128::from_le_bytes(data.try_into().unwrap())
+}
+
+#[test]
+fn stake_points_accrue_per_token_second() {
+    let mut pool = points_pool();
+    let [alice, bob] = [(); 2].map(|_| pool.staker(1_000));
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    pool.ledger.advance_clock(10);
+    pool.ledger.process(pool.stake(&bob, 500)).unwrap();
+    pool.ledger.advance_clock(10);
+
+    assert_eq!(stake_points(&mut pool, &alice), 20_000);
+    assert_eq!(stake_points(&mut pool, &bob), 5_000);
+
+    // Points already earned stay after unstaking
+    pool.ledger.process(pool.unstake(&alice, 1_000)).unwrap();
+    pool.ledger.advance_clock(10);
+    assert_eq!(stake_points(&mut pool, &alice), 20_000);
+    assert_eq!(stake_points(&mut pool, &bob), 10_000);
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 1_000);
+}
+
+#[test]
+fn points_pools_cannot_be_scheduled_rewards() {
+    let mut pool = points_pool();
+    let start_time = pool.ledger.now() + 100;
+    let schedule = staking_ix(
+        staking::accounts::SetRewardSchedule {
+            authority: pool.authority,
+            stake_pool: pool.stake_pool,
+        },
+        staking::instruction::SetRewardSchedule {
+            schedule: vec![RewardSegment {
+                start_time,
+                reward_rate: 1,
+            }],
+        },
+    );
+    assert!(pool.ledger.process(schedule).is_err());
+    assert!(pool.pool().reward_schedule.is_empty());
+}
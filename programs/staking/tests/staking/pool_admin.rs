@@ -0,0 +1,118 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction};
+use anchor_spl::token::spl_token;
+
+use crate::fixtures::{staking_ix, Staker, TestPool};
+
+fn set_paused(pool: &TestPool, authority: Pubkey, paused: bool) -> Instruction {
+    staking_ix(
+        staking::accounts::SetPaused {
+            authority,
+            stake_pool: pool.stake_pool,
+        },
+        staking::instruction::SetPaused { paused },
+    )
+}
+
+fn emergency_withdraw(pool: &TestPool, staker: &Staker) -> Instruction {
+    staking_ix(
+        staking::accounts::EmergencyWithdraw {
+            user: staker.wallet,
+            stake_pool: pool.stake_pool,
+            user_stake_account: pool.user_stake(staker.wallet),
+            user_token_account: staker.tokens,
+            pool_stake_vault: pool.pool_stake_vault,
+            stake_mint: pool.mint,
+            token_program: spl_token::ID,
+        },
+        staking::instruction::EmergencyWithdraw {},
+    )
+}
+
+#[test]
+fn paused_pools_let_stakers_out_without_their_rewards() {
+    let mut pool = TestPool::new(|pool| pool.min_stake_duration = 100);
+    let [alice, bob] = [(); 2].map(|_| pool.staker(1_000));
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    pool.ledger.advance_clock(10);
+
+    // Still locked, the stake cannot be pulled out early
+    assert!(pool
+        .ledger
+        .process(emergency_withdraw(&pool, &alice))
+        .is_err());
+    assert!(pool
+        .ledger
+        .process(set_paused(&pool, alice.wallet, true))
+        .is_err());
+    pool.ledger
+        .process(set_paused(&pool, pool.authority, true))
+        .unwrap();
+    assert!(pool.ledger.process(pool.stake(&bob, 1_000)).is_err());
+
+    // Paused, it can, and its rewards are forfeited
+    pool.ledger
+        .process(emergency_withdraw(&pool, &alice))
+        .unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 1_000);
+    let position = pool.position(&alice);
+    assert_eq!((position.amount_staked, position.pending_rewards), (0, 0));
+    assert_eq!(pool.pool().total_staked, 0);
+    assert!(pool
+        .ledger
+        .process(emergency_withdraw(&pool, &alice))
+        .is_err());
+
+    pool.ledger
+        .process(set_paused(&pool, pool.authority, false))
+        .unwrap();
+    pool.ledger.process(pool.stake(&bob, 1_000)).unwrap();
+}
+
+#[test]
+fn authority_moves_only_once_the_new_key_accepts() {
+    let mut pool = TestPool::new(|_| {});
+    let [successor, stranger] = [(); 2].map(|_| Pubkey::new_unique());
+    let propose = |authority, new_authority| {
+        staking_ix(
+            staking::accounts::ProposeAuthority {
+                authority,
+                stake_pool: pool.stake_pool,
+            },
+            staking::instruction::ProposeAuthority { new_authority },
+        )
+    };
+    let accept = |new_authority| {
+        staking_ix(
+            staking::accounts::AcceptAuthority {
+                new_authority,
+                stake_pool: pool.stake_pool,
+            },
+            staking::instruction::AcceptAuthority {},
+        )
+    };
+    let (propose_by_stranger, propose_successor) = (
+        propose(stranger, stranger),
+        propose(pool.authority, successor),
+    );
+    let (accept_by_stranger, accept_by_successor) = (accept(stranger), accept(successor));
+
+    assert!(pool.ledger.process(propose_by_stranger).is_err());
+    pool.ledger.process(propose_successor).unwrap();
+    assert_eq!(pool.pool().pending_authority, Some(successor));
+    assert!(pool.ledger.process(accept_by_stranger).is_err());
+    assert_eq!(pool.pool().authority, pool.authority);
+
+    pool.ledger.process(accept_by_successor).unwrap();
+    let state = pool.pool();
+    assert_eq!(
+        (state.authority, state.pending_authority),
+        (successor, None)
+    );
+    assert!(pool
+        .ledger
+        .process(set_paused(&pool, pool.authority, true))
+        .is_err());
+    pool.ledger
+        .process(set_paused(&pool, successor, true))
+        .unwrap();
+}
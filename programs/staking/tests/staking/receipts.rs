@@ -0,0 +1,262 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction, system_program, Space};
+use anchor_spl::token::spl_token;
+use native_runtime::Ledger;
+use staking::state::{StakePool, UserStakeAccount};
+
+use crate::fixtures::{new_ledger, staking_ix};
+
+/// A pool paying 5 reward tokens a second in its stake mint, with receipts
+/// and a slash treasury.
+struct ReceiptPool {
+    ledger: Ledger,
+    authority: Pubkey,
+    slash_treasury: Pubkey,
+    stake_mint: Pubkey,
+    stake_pool: Pubkey,
+    receipt_mint: Pubkey,
+    receipt_stake: Pubkey,
+    pool_stake_vault: Pubkey,
+    pool_reward_vault: Pubkey,
+}
+
+impl ReceiptPool {
+    fn new(reward_vault_balance: u64) -> Self {
+        let mut ledger = new_ledger();
+        let stake_mint = Pubkey::new_unique();
+        let (stake_pool, pool_bump) = Pubkey::find_program_address(
+            &[b"stake_pool", stake_mint.as_ref(), stake_mint.as_ref()],
+            &staking::ID,
+        );
+        let (receipt_mint, _) =
+            Pubkey::find_program_address(&[b"receipt_mint", stake_pool.as_ref()], &staking::ID);
+        let (receipt_stake, _) =
+            Pubkey::find_program_address(&[b"receipt_stake", stake_pool.as_ref()], &staking::ID);
+        let (pool_stake_vault, _) = Pubkey::find_program_address(
+            &[b"stake_vault", stake_pool.as_ref(), stake_mint.as_ref()],
+            &staking::ID,
+        );
+        let (pool_reward_vault, _) = Pubkey::find_program_address(
+            &[b"reward_vault", stake_pool.as_ref(), stake_mint.as_ref()],
+            &staking::ID,
+        );
+        let [authority, slash_treasury] = [(); 2].map(|_| Pubkey::new_unique());
+        let pool = StakePool {
+            authority,
+            slash_treasury: Some(slash_treasury),
+            stake_mint,
+            reward_mint: stake_mint,
+            reward_rate: 5_000_000_000,
+            last_updated: ledger.now(),
+            receipt_mint: Some(receipt_mint),
+            bump: pool_bump,
+            version: 1,
+            ..StakePool::default()
+        };
+        ledger.set_anchor(stake_pool, staking::ID, &pool, 8 + StakePool::INIT_SPACE);
+        ledger.mint(stake_mint, Pubkey::new_unique(), 1_000_000);
+        ledger.mint(receipt_mint, stake_pool, 0);
+        ledger.token_account(pool_stake_vault, stake_mint, stake_pool, 0);
+        ledger.token_account(
+            pool_reward_vault,
+            stake_mint,
+            stake_pool,
+            reward_vault_balance,
+        );
+        ledger.fund(authority);
+        ledger.token_account(slash_treasury, stake_mint, authority, 0);
+        Self {
+            ledger,
+            authority,
+            slash_treasury,
+            stake_mint,
+            stake_pool,
+            receipt_mint,
+            receipt_stake,
+            pool_stake_vault,
+            pool_reward_vault,
+        }
+    }
+
+    /// A funded holder with `tokens` stake tokens and an empty receipt account.
+    fn holder(&mut self, tokens: u64) -> (Pubkey, Pubkey, Pubkey) {
+        let [holder, token_account, receipt_account] = [(); 3].map(|_| Pubkey::new_unique());
+        self.ledger.fund(holder);
+        self.ledger
+            .token_account(token_account, self.stake_mint, holder, tokens);
+        self.ledger
+            .token_account(receipt_account, self.receipt_mint, holder, 0);
+        (holder, token_account, receipt_account)
+    }
+
+    fn stake(
+        &self,
+        user: Pubkey,
+        user_token_account: Pubkey,
+        user_receipt_account: Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        staking_ix(
+            staking::accounts::StakeLiquid {
+                user,
+                stake_pool: self.stake_pool,
+                receipt_stake_account: self.receipt_stake,
+                user_token_account,
+                receipt_mint: self.receipt_mint,
+                user_receipt_account,
+                pool_stake_vault: self.pool_stake_vault,
+                pool_reward_vault: Some(self.pool_reward_vault),
+                stake_mint: self.stake_mint,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            staking::instruction::StakeLiquid { amount },
+        )
+    }
+
+    fn unstake(
+        &self,
+        holder: Pubkey,
+        holder_token_account: Pubkey,
+        holder_receipt_account: Pubkey,
+        receipts: u64,
+    ) -> Instruction {
+        staking_ix(
+            staking::accounts::UnstakeLiquid {
+                holder,
+                stake_pool: self.stake_pool,
+                receipt_stake_account: self.receipt_stake,
+                holder_token_account,
+                receipt_mint: self.receipt_mint,
+                holder_receipt_account,
+                pool_stake_vault: self.pool_stake_vault,
+                pool_reward_vault: Some(self.pool_reward_vault),
+                stake_mint: self.stake_mint,
+                token_program: spl_token::ID,
+            },
+            staking::instruction::UnstakeLiquid { receipts },
+        )
+    }
+}
+
+#[test]
+fn transferred_receipts_redeem_their_share_of_the_receipt_stake() {
+    let mut pool = ReceiptPool::new(10_000);
+    let (alice, alice_tokens, alice_receipts) = pool.holder(1_000);
+    let (bob, bob_tokens, bob_receipts) = pool.holder(0);
+
+    let stake = pool.stake(alice, alice_tokens, alice_receipts, 1_000);
+    pool.ledger.process(stake).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice_receipts), 1_000);
+
+    // The receipt stake earns 500 while Alice hands half her receipts to Bob
+    pool.ledger.advance_clock(100);
+    pool.ledger
+        .process(
+            spl_token::instruction::transfer(
+                &spl_token::ID,
+                &alice_receipts,
+                &bob_receipts,
+                &alice,
+                &[],
+                500,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+    // Receipts are burned from the holder's own account only
+    assert!(pool
+        .ledger
+        .process(pool.unstake(alice, alice_tokens, bob_receipts, 500))
+        .is_err());
+    assert!(pool
+        .ledger
+        .process(pool.unstake(alice, alice_tokens, alice_receipts, 600))
+        .is_err());
+
+    // Bob redeems the receipts he was given for half the stake and its rewards
+    let unstake = pool.unstake(bob, bob_tokens, bob_receipts, 500);
+    pool.ledger.process(unstake).unwrap();
+    assert_eq!(pool.ledger.token_balance(&bob_tokens), 750);
+    assert_eq!(pool.ledger.token_balance(&bob_receipts), 0);
+    assert_eq!(
+        pool.ledger
+            .get::<UserStakeAccount>(&pool.receipt_stake)
+            .amount_staked,
+        750
+    );
+    assert_eq!(
+        pool.ledger.get::<StakePool>(&pool.stake_pool).total_staked,
+        750
+    );
+
+    let unstake = pool.unstake(alice, alice_tokens, alice_receipts, 500);
+    pool.ledger.process(unstake).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice_tokens), 750);
+}
+
+#[test]
+fn receipts_redeem_their_principal_while_the_reward_vault_is_empty() {
+    let mut pool = ReceiptPool::new(0);
+    let (alice, alice_tokens, alice_receipts) = pool.holder(1_000);
+
+    let stake = pool.stake(alice, alice_tokens, alice_receipts, 1_000);
+    pool.ledger.process(stake).unwrap();
+
+    // The receipt stake earns 500 the vault cannot pay yet
+    pool.ledger.advance_clock(100);
+    let unstake = pool.unstake(alice, alice_tokens, alice_receipts, 1_000);
+    pool.ledger.process(unstake).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice_tokens), 1_000);
+    assert_eq!(pool.ledger.token_balance(&alice_receipts), 0);
+    let receipt_stake = pool.ledger.get::<UserStakeAccount>(&pool.receipt_stake);
+    assert_eq!(receipt_stake.amount_staked, 0);
+    assert_eq!(receipt_stake.pending_rewards, 500);
+}
+
+#[test]
+fn slashing_the_receipt_stake_cuts_every_receipt() {
+    let mut pool = ReceiptPool::new(0);
+    let (alice, alice_tokens, alice_receipts) = pool.holder(1_000);
+    let (bob, bob_tokens, bob_receipts) = pool.holder(1_000);
+    let stake = pool.stake(alice, alice_tokens, alice_receipts, 1_000);
+    pool.ledger.process(stake).unwrap();
+    let stake = pool.stake(bob, bob_tokens, bob_receipts, 1_000);
+    pool.ledger.process(stake).unwrap();
+
+    let slash = |slasher| {
+        staking_ix(
+            staking::accounts::Slash {
+                slasher,
+                stake_pool: pool.stake_pool,
+                user_stake_account: pool.receipt_stake,
+                pool_stake_vault: pool.pool_stake_vault,
+                slash_treasury: pool.slash_treasury,
+                stake_mint: pool.stake_mint,
+                token_program: spl_token::ID,
+            },
+            staking::instruction::Slash {
+                user: pool.stake_pool,
+                bps: 2_000,
+                reason_code: 1,
+            },
+        )
+    };
+    // A receipt holder cannot slash
+    assert!(pool.ledger.process(slash(alice)).is_err());
+
+    pool.ledger.process(slash(pool.authority)).unwrap();
+    assert_eq!(pool.ledger.token_balance(&pool.slash_treasury), 400);
+    assert_eq!(
+        pool.ledger.get::<StakePool>(&pool.stake_pool).total_staked,
+        1_600
+    );
+
+    // Both holders lose a fifth, however they got their receipts
+    let unstake = pool.unstake(alice, alice_tokens, alice_receipts, 1_000);
+    pool.ledger.process(unstake).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice_tokens), 800);
+    let unstake = pool.unstake(bob, bob_tokens, bob_receipts, 1_000);
+    pool.ledger.process(unstake).unwrap();
+    assert_eq!(pool.ledger.token_balance(&bob_tokens), 800);
+}
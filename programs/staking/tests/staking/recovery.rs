@@ -0,0 +1,119 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction};
+use anchor_spl::token::spl_token;
+use staking::state::RewardSegment;
+
+use crate::fixtures::{staking_ix, TestPool};
+
+fn recover(
+    pool: &TestPool,
+    source: Pubkey,
+    destination: Pubkey,
+    mint: Pubkey,
+    amount: u64,
+) -> Instruction {
+    staking_ix(
+        staking::accounts::RecoverToken {
+            authority: pool.authority,
+            stake_pool: pool.stake_pool,
+            source,
+            destination,
+            mint,
+            token_program: spl_token::ID,
+        },
+        staking::instruction::RecoverToken { amount },
+    )
+}
+
+#[test]
+fn only_rewards_nobody_is_owed_can_be_withdrawn() {
+    let mut pool = TestPool::new(|_| {});
+    let alice = pool.staker(1_000);
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    let end = pool.ledger.now() + 100;
+    pool.ledger
+        .process(staking_ix(
+            staking::accounts::SetRewardSchedule {
+                authority: pool.authority,
+                stake_pool: pool.stake_pool,
+            },
+            staking::instruction::SetRewardSchedule {
+                schedule: vec![RewardSegment {
+                    start_time: end,
+                    reward_rate: 0,
+                }],
+            },
+        ))
+        .unwrap();
+    let treasury = pool.staker(0).tokens;
+    let withdraw = |authority, amount| {
+        staking_ix(
+            staking::accounts::WithdrawExcessRewards {
+                authority,
+                stake_pool: pool.stake_pool,
+                pool_reward_vault: pool.pool_reward_vault,
+                destination: treasury,
+                reward_mint: pool.mint,
+                token_program: spl_token::ID,
+            },
+            staking::instruction::WithdrawExcessRewards { amount },
+        )
+    };
+    let [too_much, by_alice, excess] = [
+        withdraw(pool.authority, 999_901),
+        withdraw(alice.wallet, 999_900),
+        withdraw(pool.authority, 999_900),
+    ];
+
+    // 50 tokens are owed to Alice and 50 more are still scheduled
+    pool.ledger.advance_clock(50);
+    assert!(pool.ledger.process(too_much).is_err());
+    assert!(pool.ledger.process(by_alice).is_err());
+    pool.ledger.process(excess).unwrap();
+    assert_eq!(pool.ledger.token_balance(&treasury), 999_900);
+
+    // Long after the schedule ends, the vault holds exactly what was owed
+    pool.ledger.advance_clock(1_000);
+    pool.ledger
+        .process(pool.compound(alice.wallet, &alice))
+        .unwrap();
+    assert_eq!(pool.position(&alice).amount_staked, 1_100);
+    assert_eq!(pool.ledger.token_balance(&pool.pool_reward_vault), 0);
+}
+
+#[test]
+fn stray_tokens_are_recovered_but_not_stake_or_rewards() {
+    let mut pool = TestPool::new(|_| {});
+    let alice = pool.staker(1_000);
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    let treasury = pool.staker(0).tokens;
+
+    // 300 tokens sent straight to the stake vault
+    pool.ledger
+        .token_account(pool.pool_stake_vault, pool.mint, pool.stake_pool, 1_300);
+    let stake_vault = pool.pool_stake_vault;
+    assert!(pool
+        .ledger
+        .process(recover(&pool, stake_vault, treasury, pool.mint, 301))
+        .is_err());
+    pool.ledger
+        .process(recover(&pool, stake_vault, treasury, pool.mint, 300))
+        .unwrap();
+    let reward_vault = pool.pool_reward_vault;
+    assert!(pool
+        .ledger
+        .process(recover(&pool, reward_vault, treasury, pool.mint, 1))
+        .is_err());
+
+    // Any other token account the pool owns is given up whole
+    let [foreign_mint, stray, destination] = [(); 3].map(|_| Pubkey::new_unique());
+    pool.ledger.mint(foreign_mint, Pubkey::new_unique(), 500);
+    pool.ledger
+        .token_account(stray, foreign_mint, pool.stake_pool, 500);
+    pool.ledger
+        .token_account(destination, foreign_mint, pool.authority, 0);
+    pool.ledger
+        .process(recover(&pool, stray, destination, foreign_mint, 500))
+        .unwrap();
+    assert_eq!(pool.ledger.token_balance(&destination), 500);
+    assert_eq!(pool.ledger.token_balance(&pool.pool_stake_vault), 1_000);
+}
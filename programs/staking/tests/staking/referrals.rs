@@ -0,0 +1,105 @@
+use anchor_lang::{
+    prelude::*, solana_program::instruction::Instruction, system_program, InstructionData,
+};
+use anchor_spl::token::spl_token;
+
+use crate::fixtures::{staking_ix, Staker, TestPool};
+
+/// A pool paying referrers 10% on top, out of a budget of 15, with Alice
+/// staking 1_000 referred by Rex.
+fn referral_pool(reward_vesting_duration: i64) -> (TestPool, Staker, Staker) {
+    let mut pool = TestPool::new(|pool| pool.reward_vesting_duration = reward_vesting_duration);
+    let [alice, rex] = [pool.staker(1_000), pool.staker(0)];
+    let set_referral_bps = |referral_budget| {
+        staking_ix(
+            staking::accounts::SetReferralBps {
+                authority: pool.authority,
+                stake_pool: pool.stake_pool,
+                pool_reward_vault: Some(pool.pool_reward_vault),
+            },
+            staking::instruction::SetReferralBps {
+                referral_bps: 1_000,
+                referral_budget,
+            },
+        )
+    };
+    // The budget has to be covered by the reward vault
+    let (unfunded, funded) = (set_referral_bps(1_000_001), set_referral_bps(15));
+    assert!(pool.ledger.process(unfunded).is_err());
+    pool.ledger.process(funded).unwrap();
+
+    let mut stake = pool.stake(&alice, 1_000);
+    stake.data = staking::instruction::Stake {
+        amount: 1_000,
+        referrer: Some(rex.wallet),
+    }
+    .data();
+    pool.ledger.process(stake).unwrap();
+    (pool, alice, rex)
+}
+
+fn claim_referral(pool: &TestPool, referrer: &Staker, user: &Staker, vests: bool) -> Instruction {
+    staking_ix(
+        staking::accounts::ClaimReferralRewards {
+            referrer: referrer.wallet,
+            stake_pool: pool.stake_pool,
+            user_stake_account: pool.user_stake(user.wallet),
+            referrer_stake_account: vests.then(|| pool.user_stake(referrer.wallet)),
+            referrer_reward_account: referrer.tokens,
+            pool_reward_vault: pool.pool_reward_vault,
+            reward_mint: pool.mint,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        staking::instruction::ClaimReferralRewards {},
+    )
+}
+
+#[test]
+fn referrers_earn_on_top_until_the_budget_runs_out() {
+    let (mut pool, alice, rex) = referral_pool(0);
+
+    // 10 on Alice's first 100, then only the 5 left in the budget
+    pool.ledger.advance_clock(100);
+    pool.ledger.process(pool.claim(&alice)).unwrap();
+    pool.ledger.advance_clock(100);
+    pool.ledger.process(pool.claim(&alice)).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 200);
+    let state = pool.pool();
+    assert_eq!((state.referral_budget, state.rewards_owed), (0, 15));
+
+    // Only Rex can claim the bonus, and only once
+    let mallory = pool.staker(0);
+    assert!(pool
+        .ledger
+        .process(claim_referral(&pool, &mallory, &alice, false))
+        .is_err());
+    pool.ledger
+        .process(claim_referral(&pool, &rex, &alice, false))
+        .unwrap();
+    assert_eq!(pool.ledger.token_balance(&rex.tokens), 15);
+    assert!(pool
+        .ledger
+        .process(claim_referral(&pool, &rex, &alice, false))
+        .is_err());
+    assert_eq!(pool.pool().rewards_owed, 0);
+}
+
+#[test]
+fn referral_bonuses_vest_with_the_pool_rewards() {
+    let (mut pool, alice, rex) = referral_pool(100);
+    pool.ledger.advance_clock(100);
+
+    // Vesting needs Rex's stake account, opened for him if he has none
+    assert!(pool
+        .ledger
+        .process(claim_referral(&pool, &rex, &alice, false))
+        .is_err());
+    pool.ledger
+        .process(claim_referral(&pool, &rex, &alice, true))
+        .unwrap();
+    assert_eq!(pool.ledger.token_balance(&rex.tokens), 0);
+    let vesting = pool.position(&rex).vesting;
+    assert_eq!((vesting.len(), vesting[0].amount), (1, 10));
+    assert_eq!(pool.position(&rex).amount_staked, 0);
+}
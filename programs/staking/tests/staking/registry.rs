@@ -0,0 +1,219 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction, system_program};
+use anchor_spl::token::spl_token;
+use native_runtime::Ledger;
+use staking::state::{EarlyExitConfig, PoolEntry, PoolKind, PoolLimits, PoolRegistry, StakePool};
+
+use crate::fixtures::staking_ix;
+
+/// A ledger with the config set up by `admin`, the upgrade authority, who is
+/// also trusted to open pools. Anyone else pays a creation fee.
+struct Registry {
+    ledger: Ledger,
+    admin: Pubkey,
+    config: Pubkey,
+    pool_registry: Pubkey,
+    fee_recipient: Pubkey,
+}
+
+fn pool_entry(index: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"pool_entry", &index.to_le_bytes()], &staking::ID).0
+}
+
+impl Registry {
+    fn new() -> Self {
+        let mut ledger = Ledger::new();
+        let [admin, fee_recipient] = [(); 2].map(|_| Pubkey::new_unique());
+        ledger.fund(admin);
+        ledger.fund(fee_recipient);
+        let program_data = ledger.add_upgradeable_program(staking::ID, staking::entry, admin);
+        let (config, _) = Pubkey::find_program_address(&[b"config"], &staking::ID);
+        let (pool_registry, _) = Pubkey::find_program_address(&[b"pool_registry"], &staking::ID);
+        ledger
+            .process(staking_ix(
+                staking::accounts::InitializeConfig {
+                    admin,
+                    config,
+                    pool_registry,
+                    program: staking::ID,
+                    program_data,
+                    system_program: system_program::ID,
+                },
+                staking::instruction::InitializeConfig {
+                    pool_creators: vec![admin],
+                    creation_fee: Some(1_000_000),
+                    fee_recipient,
+                },
+            ))
+            .unwrap();
+        Self {
+            ledger,
+            admin,
+            config,
+            pool_registry,
+            fee_recipient,
+        }
+    }
+
+    fn pool_count(&self) -> u64 {
+        self.ledger
+            .get::<PoolRegistry>(&self.pool_registry)
+            .pool_count
+    }
+
+    /// `creator` opens a pool staking a fresh mint, listed at `entry`.
+    /// Returns the pool's address with the instruction.
+    fn initialize_pool(&mut self, creator: Pubkey, entry: Pubkey) -> (Pubkey, Instruction) {
+        let [stake_mint, reward_mint] = [(); 2].map(|_| Pubkey::new_unique());
+        self.ledger.mint(stake_mint, creator, 0);
+        self.ledger.mint(reward_mint, creator, 0);
+        let creator_seed: &[u8] = if creator == self.admin {
+            &[]
+        } else {
+            creator.as_ref()
+        };
+        let (stake_pool, _) = Pubkey::find_program_address(
+            &[
+                b"stake_pool",
+                stake_mint.as_ref(),
+                reward_mint.as_ref(),
+                creator_seed,
+            ],
+            &staking::ID,
+        );
+        let [pool_stake_vault, pool_reward_vault] = [
+            (&b"stake_vault"[..], stake_mint),
+            (b"reward_vault", reward_mint),
+        ]
+        .map(|(seed, mint)| {
+            Pubkey::find_program_address(&[seed, stake_pool.as_ref(), mint.as_ref()], &staking::ID)
+                .0
+        });
+        let ix = staking_ix(
+            staking::accounts::InitializePool {
+                authority: creator,
+                config: self.config,
+                pool_registry: self.pool_registry,
+                pool_entry: entry,
+                fee_recipient: self.fee_recipient,
+                stake_mint,
+                reward_mint,
+                stake_pool,
+                pool_stake_vault,
+                pool_reward_vault,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            staking::instruction::InitializePool {
+                reward_rate: 100,
+                minimum_stake_duration: 0,
+                early_exit: EarlyExitConfig {
+                    penalty_bps: 0,
+                    linear_decay: false,
+                    treasury: None,
+                },
+                limits: PoolLimits {
+                    max_total_staked: None,
+                    max_per_user: None,
+                    min_stake_amount: 0,
+                },
+                reward_vesting_duration: 0,
+            },
+        );
+        (stake_pool, ix)
+    }
+
+    fn register_pool(&self, stake_pool: Pubkey, entry: Pubkey) -> Instruction {
+        staking_ix(
+            staking::accounts::RegisterPool {
+                admin: self.admin,
+                config: self.config,
+                pool_registry: self.pool_registry,
+                stake_pool,
+                pool_entry: entry,
+                system_program: system_program::ID,
+            },
+            staking::instruction::RegisterPool {},
+        )
+    }
+}
+
+#[test]
+fn pools_are_listed_in_order_and_walked_by_index() {
+    let mut registry = Registry::new();
+    let community = Pubkey::new_unique();
+    registry.ledger.fund(community);
+
+    let admin = registry.admin;
+    let (official_pool, ix) = registry.initialize_pool(admin, pool_entry(0));
+    registry.ledger.process(ix).unwrap();
+    let (community_pool, ix) = registry.initialize_pool(community, pool_entry(1));
+    registry.ledger.process(ix).unwrap();
+
+    // A pool opened before the registry is listed by the admin
+    let mut pool = StakePool {
+        authority: Pubkey::new_unique(),
+        stake_mint: Pubkey::new_unique(),
+        reward_mint: Pubkey::new_unique(),
+        version: 1,
+        ..StakePool::default()
+    };
+    let (legacy_pool, bump) = Pubkey::find_program_address(
+        &[
+            b"stake_pool",
+            pool.stake_mint.as_ref(),
+            pool.reward_mint.as_ref(),
+        ],
+        &staking::ID,
+    );
+    pool.bump = bump;
+    registry
+        .ledger
+        .set_anchor(legacy_pool, staking::ID, &pool, 8 + StakePool::INIT_SPACE);
+    registry
+        .ledger
+        .process(registry.register_pool(legacy_pool, pool_entry(2)))
+        .unwrap();
+
+    // Clients need nothing but the count to find every pool
+    assert_eq!(registry.pool_count(), 3);
+    let listed: Vec<PoolEntry> = (0..registry.pool_count())
+        .map(|index| registry.ledger.get(&pool_entry(index)))
+        .collect();
+    let pools: Vec<_> = listed
+        .iter()
+        .map(|entry| (entry.pool, entry.index, entry.official))
+        .collect();
+    assert_eq!(
+        pools,
+        [
+            (official_pool, 0, true),
+            (community_pool, 1, false),
+            (legacy_pool, 2, true),
+        ]
+    );
+    assert!(listed.iter().all(|entry| entry.kind == PoolKind::Token));
+    assert_eq!(listed[1].creator, community);
+    assert!(registry.ledger.get::<StakePool>(&legacy_pool).registered);
+}
+
+#[test]
+fn pools_are_listed_once_and_at_the_next_index() {
+    let mut registry = Registry::new();
+    let admin = registry.admin;
+
+    // An entry must sit at the registry's next index
+    let (_, ix) = registry.initialize_pool(admin, pool_entry(1));
+    assert!(registry.ledger.process(ix).is_err());
+    let (stake_pool, ix) = registry.initialize_pool(admin, pool_entry(0));
+    registry.ledger.process(ix).unwrap();
+    let (_, ix) = registry.initialize_pool(admin, pool_entry(0));
+    assert!(registry.ledger.process(ix).is_err());
+    assert_eq!(registry.pool_count(), 1);
+
+    // A listed pool cannot take a second entry
+    assert!(registry
+        .ledger
+        .process(registry.register_pool(stake_pool, pool_entry(1)))
+        .is_err());
+    assert_eq!(registry.pool_count(), 1);
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use staking::state::RewardSegment;
+
+use crate::fixtures::{staking_ix, TestPool};
+
+/// `PRECISION` in the program: `reward_rate` is scaled by it.
+const PRECISION: u64 = 1_000_000_000;
+
+#[test]
+fn rewards_follow_the_scheduled_rates() {
+    let mut pool = TestPool::new(|_| {});
+    let alice = pool.staker(1_000);
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    let start = pool.ledger.now();
+    let set_schedule = |authority, schedule| {
+        staking_ix(
+            staking::accounts::SetRewardSchedule {
+                authority,
+                stake_pool: pool.stake_pool,
+            },
+            staking::instruction::SetRewardSchedule { schedule },
+        )
+    };
+    let segment = |offset, tokens_per_second| RewardSegment {
+        start_time: start + offset,
+        reward_rate: tokens_per_second * PRECISION,
+    };
+
+    // Segments must be in the future and in order, and set by the authority
+    let rejected = [
+        set_schedule(pool.authority, vec![segment(0, 3)]),
+        set_schedule(pool.authority, vec![segment(200, 0), segment(100, 3)]),
+        set_schedule(alice.wallet, vec![segment(100, 3), segment(200, 0)]),
+    ];
+    for ix in rejected {
+        assert!(pool.ledger.process(ix).is_err());
+    }
+    pool.ledger
+        .process(set_schedule(
+            pool.authority,
+            vec![segment(100, 3), segment(200, 0)],
+        ))
+        .unwrap();
+
+    // 100 seconds at 1, 100 at 3, then nothing
+    pool.ledger.advance_clock(300);
+    pool.ledger
+        .process(pool.compound(alice.wallet, &alice))
+        .unwrap();
+    assert_eq!(pool.position(&alice).amount_staked, 1_400);
+    let state = pool.pool();
+    assert_eq!(state.reward_rate, 0);
+    assert!(state.reward_schedule.is_empty());
+}
@@ -0,0 +1,47 @@
+/// `MAX_STAKE_LOTS` in the program.
+const MAX_STAKE_LOTS: usize = 10;
+
+use crate::fixtures::TestPool;
+
+#[test]
+fn funders_lock_stake_for_offline_beneficiaries() {
+    let mut pool = TestPool::new(|_| {});
+    let funder = pool.staker(10_000);
+    let alice = pool.staker(100);
+    let lock_until = pool.ledger.now() + 1_000;
+
+    // Alice never signs, and cannot withdraw before the lock ends
+    let stake_for = pool.stake_for(&funder, alice.wallet, 500, Some(lock_until));
+    pool.ledger.process(stake_for).unwrap();
+    let position = pool.position(&alice);
+    assert_eq!(position.amount_staked, 500);
+    assert_eq!(position.lots[0].unlock_time, lock_until);
+    assert!(pool.ledger.process(pool.unstake(&alice, 500)).is_err());
+
+    pool.ledger.advance_clock(1_000);
+    pool.ledger.process(pool.unstake(&alice, 500)).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 600);
+}
+
+#[test]
+fn funders_cannot_lock_the_last_free_lot() {
+    let mut pool = TestPool::new(|_| {});
+    let funder = pool.staker(10_000);
+    let alice = pool.staker(100);
+    let lock_until = pool.ledger.now() + 1_000;
+
+    for _ in 0..MAX_STAKE_LOTS - 1 {
+        let stake_for = pool.stake_for(&funder, alice.wallet, 100, Some(lock_until));
+        pool.ledger.process(stake_for).unwrap();
+    }
+    let stake_for = pool.stake_for(&funder, alice.wallet, 100, Some(lock_until));
+    assert!(pool.ledger.process(stake_for).is_err());
+
+    // Alice's own deposits still find a lot, the last one and then by merging
+    pool.ledger.process(pool.stake(&alice, 50)).unwrap();
+    pool.ledger.process(pool.stake(&alice, 50)).unwrap();
+    let position = pool.position(&alice);
+    assert_eq!(position.lots.len(), MAX_STAKE_LOTS);
+    assert_eq!(position.lots[MAX_STAKE_LOTS - 1].amount, 100);
+    assert_eq!(position.amount_staked, 1_000);
+}
@@ -0,0 +1,153 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction, system_program};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+    },
+    state::{Account, AccountState, Mint},
+};
+use native_runtime::Ledger;
+
+use crate::fixtures::{staking_ix, Staker, TestPool};
+
+/// A Token-2022 mint charging `fee_bps` on every transfer.
+fn fee_mint(ledger: &mut Ledger, mint: Pubkey, fee_bps: u16) {
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig])
+            .unwrap();
+    let mut data = vec![0; space];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    let fee = TransferFee {
+        epoch: 0.into(),
+        maximum_fee: u64::MAX.into(),
+        transfer_fee_basis_points: fee_bps.into(),
+    };
+    *state.init_extension::<TransferFeeConfig>(true).unwrap() = TransferFeeConfig {
+        older_transfer_fee: fee,
+        newer_transfer_fee: fee,
+        ..TransferFeeConfig::default()
+    };
+    state.base = Mint {
+        supply: 1_000_000_000,
+        decimals: 6,
+        is_initialized: true,
+        ..Mint::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    ledger.set(mint, spl_token_2022::ID, 1_000_000_000, data, false);
+}
+
+/// A token account of a fee mint, able to hold withheld fees.
+fn fee_token_account(ledger: &mut Ledger, key: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) {
+    let space =
+        ExtensionType::try_calculate_account_len::<Account>(&[ExtensionType::TransferFeeAmount])
+            .unwrap();
+    let mut data = vec![0; space];
+    let mut state = StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut data).unwrap();
+    state.init_extension::<TransferFeeAmount>(true).unwrap();
+    state.base = Account {
+        mint,
+        owner,
+        amount,
+        state: AccountState::Initialized,
+        ..Account::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    ledger.set(key, spl_token_2022::ID, 1_000_000_000, data, false);
+}
+
+/// A pool staking and paying out a mint with a 1% transfer fee.
+fn fee_pool(min_stake_amount: u64) -> TestPool {
+    let mint = Pubkey::new_unique();
+    let mut pool = TestPool::new(|pool| {
+        pool.stake_mint = mint;
+        pool.reward_mint = mint;
+        pool.limits.min_stake_amount = min_stake_amount;
+    });
+    fee_mint(&mut pool.ledger, mint, 100);
+    pool.mint = mint;
+    let (stake_pool, stake_vault) = (pool.stake_pool, pool.pool_stake_vault);
+    fee_token_account(&mut pool.ledger, stake_vault, mint, stake_pool, 0);
+    let reward_vault = pool.pool_reward_vault;
+    fee_token_account(&mut pool.ledger, reward_vault, mint, stake_pool, 1_000_000);
+    pool
+}
+
+fn fee_staker(pool: &mut TestPool, tokens: u64) -> Staker {
+    let staker = pool.staker(0);
+    fee_token_account(
+        &mut pool.ledger,
+        staker.tokens,
+        pool.mint,
+        staker.wallet,
+        tokens,
+    );
+    staker
+}
+
+fn stake(pool: &TestPool, staker: &Staker, amount: u64) -> Instruction {
+    staking_ix(
+        staking::accounts::Stake {
+            user: staker.wallet,
+            stake_pool: pool.stake_pool,
+            user_stake_account: pool.user_stake(staker.wallet),
+            user_token_account: staker.tokens,
+            pool_stake_vault: pool.pool_stake_vault,
+            stake_mint: pool.mint,
+            token_program: spl_token_2022::ID,
+            system_program: system_program::ID,
+        },
+        staking::instruction::Stake {
+            amount,
+            referrer: None,
+        },
+    )
+}
+
+fn unstake(pool: &TestPool, staker: &Staker, amount: u64) -> Instruction {
+    staking_ix(
+        staking::accounts::Unstake {
+            user: staker.wallet,
+            user_stake_account: pool.user_stake(staker.wallet),
+            user_token_account: staker.tokens,
+            stake_pool: pool.stake_pool,
+            pool_stake_vault: pool.pool_stake_vault,
+            pool_reward_vault: Some(pool.pool_reward_vault),
+            penalty_treasury: None,
+            stake_mint: pool.mint,
+            token_program: spl_token_2022::ID,
+        },
+        staking::instruction::Unstake { amount },
+    )
+}
+
+#[test]
+fn stakes_are_credited_net_of_the_transfer_fee() {
+    let mut pool = fee_pool(0);
+    let alice = fee_staker(&mut pool, 1_000);
+
+    pool.ledger.process(stake(&pool, &alice, 1_000)).unwrap();
+    assert_eq!(pool.ledger.token_balance(&pool.pool_stake_vault), 990);
+    assert_eq!(pool.position(&alice).amount_staked, 990);
+    assert_eq!(pool.pool().total_staked, 990);
+
+    // The vault holds exactly the credited stake, so all of it can leave
+    assert!(pool.ledger.process(unstake(&pool, &alice, 991)).is_err());
+    pool.ledger.process(unstake(&pool, &alice, 990)).unwrap();
+    assert_eq!(pool.ledger.token_balance(&pool.pool_stake_vault), 0);
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 980);
+}
+
+#[test]
+fn deposit_limits_count_what_the_vault_receives() {
+    let mut pool = fee_pool(1_000);
+    let alice = fee_staker(&mut pool, 2_000);
+
+    // 1_000 sent is 990 received, short of the minimum
+    assert!(pool.ledger.process(stake(&pool, &alice, 1_000)).is_err());
+    pool.ledger.process(stake(&pool, &alice, 1_011)).unwrap();
+    assert_eq!(pool.position(&alice).amount_staked, 1_000);
+}
@@ -0,0 +1,69 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction};
+use anchor_spl::token::spl_token;
+
+use crate::fixtures::{staking_ix, Staker, TestPool};
+
+fn withdraw_vested(pool: &TestPool, staker: &Staker) -> Instruction {
+    staking_ix(
+        staking::accounts::WithdrawVested {
+            user: staker.wallet,
+            stake_pool: pool.stake_pool,
+            user_stake_account: pool.user_stake(staker.wallet),
+            user_reward_account: staker.tokens,
+            pool_reward_vault: pool.pool_reward_vault,
+            reward_mint: pool.mint,
+            token_program: spl_token::ID,
+        },
+        staking::instruction::WithdrawVested {},
+    )
+}
+
+#[test]
+fn claimed_rewards_are_released_linearly() {
+    let mut pool = TestPool::new(|pool| pool.reward_vesting_duration = 100);
+    let alice = pool.staker(1_000);
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    pool.ledger.advance_clock(100);
+
+    // The 100 claimed start vesting instead of being paid out
+    pool.ledger.process(pool.claim(&alice)).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 0);
+    let vesting = pool.position(&alice).vesting;
+    assert_eq!((vesting.len(), vesting[0].amount), (1, 100));
+    assert!(pool.ledger.process(withdraw_vested(&pool, &alice)).is_err());
+
+    pool.ledger.advance_clock(40);
+    pool.ledger.process(withdraw_vested(&pool, &alice)).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 40);
+    assert!(pool.ledger.process(withdraw_vested(&pool, &alice)).is_err());
+
+    // Past the end the rest is released and the entry dropped
+    pool.ledger.advance_clock(1_000);
+    pool.ledger.process(withdraw_vested(&pool, &alice)).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 100);
+    assert!(pool.position(&alice).vesting.is_empty());
+}
+
+#[test]
+fn unvested_rewards_keep_the_stake_account_open() {
+    let mut pool = TestPool::new(|pool| pool.reward_vesting_duration = 100);
+    let alice = pool.staker(1_000);
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    pool.ledger.advance_clock(100);
+    pool.ledger.process(pool.unstake(&alice, 1_000)).unwrap();
+    pool.ledger.process(pool.claim(&alice)).unwrap();
+
+    let close = staking_ix(
+        staking::accounts::CloseStakeAccount {
+            user: alice.wallet,
+            stake_pool: pool.stake_pool,
+            user_stake_account: pool.user_stake(alice.wallet),
+        },
+        staking::instruction::CloseStakeAccount {},
+    );
+    assert!(pool.ledger.process(close.clone()).is_err());
+    pool.ledger.advance_clock(100);
+    pool.ledger.process(withdraw_vested(&pool, &alice)).unwrap();
+    pool.ledger.process(close).unwrap();
+    assert_eq!(pool.ledger.token_balance(&alice.tokens), 1_100);
+}
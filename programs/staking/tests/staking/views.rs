@@ -0,0 +1,69 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction, Space};
+use staking::state::UserStakeAccount;
+
+use crate::fixtures::{staking_ix, TestPool};
+
+fn get_pending_rewards(pool: &TestPool, user_stake_account: Pubkey) -> Instruction {
+    staking_ix(
+        staking::accounts::GetPendingRewards {
+            stake_pool: pool.stake_pool,
+            user_stake_account,
+        },
+        staking::instruction::GetPendingRewards {},
+    )
+}
+
+/// The `u64` that the last instruction returned.
+fn returned_u64(pool: &TestPool) -> u64 {
+    let (program, data) = pool.ledger.return_data().unwrap();
+    assert_eq!(program, staking::ID);
+    u64::from_le_bytes(data.try_into().unwrap())
+}
+
+#[test]
+fn views_return_live_values_without_writing() {
+    let mut pool = TestPool::new(|_| {});
+    let alice = pool.staker(1_000);
+    pool.ledger.process(pool.stake(&alice, 1_000)).unwrap();
+    pool.ledger.advance_clock(25);
+
+    let user_stake = pool.user_stake(alice.wallet);
+    pool.ledger
+        .process(get_pending_rewards(&pool, user_stake))
+        .unwrap();
+    assert_eq!(returned_u64(&pool), 25);
+    assert_eq!(pool.position(&alice).pending_rewards, 0);
+
+    // 1 token a second on 1_000 staked pays 31_536 times the stake a year, in bps
+    pool.ledger
+        .process(staking_ix(
+            staking::accounts::GetPoolApr {
+                stake_pool: pool.stake_pool,
+            },
+            staking::instruction::GetPoolApr {},
+        ))
+        .unwrap();
+    assert_eq!(returned_u64(&pool), 315_360_000);
+}
+
+#[test]
+fn views_reject_stake_accounts_of_other_pools() {
+    let mut pool = TestPool::new(|_| {});
+    let mut other = TestPool::new(|_| {});
+    let alice = other.staker(1_000);
+    other.ledger.process(other.stake(&alice, 1_000)).unwrap();
+    let foreign = other.position(&alice);
+    let foreign_key = other.user_stake(alice.wallet);
+    pool.ledger.set_anchor(
+        foreign_key,
+        staking::ID,
+        &foreign,
+        8 + UserStakeAccount::INIT_SPACE,
+    );
+
+    assert!(pool
+        .ledger
+        .process(get_pending_rewards(&pool, foreign_key))
+        .is_err());
+    assert!(pool.ledger.return_data().is_none());
+}